@@ -70,6 +70,62 @@ pub const YELLOW_DURATION_MS: u64 = 1000;
 #[allow(dead_code)]
 pub const GREEN_DURATION_MS: u64 = 3000;
 
+/// All-red clearance duration in milliseconds.
+///
+/// # Details
+/// Duration every approach is held at red between phases
+/// when the optional clearance interval is enabled.
+///
+/// # Value
+/// 1000 milliseconds (1 second)
+#[allow(dead_code)]
+pub const ALL_RED_DURATION_MS: u64 = 1000;
+
+/// Red+yellow "get ready" duration in milliseconds.
+///
+/// # Details
+/// Duration of the optional RedYellow phase shown between Red
+/// and Green under some regional standards.
+///
+/// # Value
+/// 1000 milliseconds (1 second)
+#[allow(dead_code)]
+pub const RED_YELLOW_DURATION_MS: u64 = 1000;
+
+/// Fault flash half-period in milliseconds.
+///
+/// # Details
+/// Time the red lamp stays on, then off, while the controller
+/// is in FlashingRed fault mode.
+///
+/// # Value
+/// 500 milliseconds (1 Hz flash)
+#[allow(dead_code)]
+pub const FAULT_FLASH_MS: u64 = 500;
+
+/// Night-mode flash half-period in milliseconds.
+///
+/// # Details
+/// Default time the yellow lamp stays on, then off, while the
+/// controller is in night-mode flashing yellow.
+///
+/// # Value
+/// 500 milliseconds (1 Hz flash)
+#[allow(dead_code)]
+pub const NIGHT_FLASH_MS: u64 = 500;
+
+/// Push-button debounce window in milliseconds.
+///
+/// # Details
+/// A falling edge only counts as a press if the button is still
+/// held down once this window has passed; contact bounce inside
+/// the window is ignored.
+///
+/// # Value
+/// 50 milliseconds
+#[allow(dead_code)]
+pub const BUTTON_DEBOUNCE_MS: u64 = 50;
+
 /// Minimum allowed light duration in milliseconds.
 ///
 /// # Details
@@ -90,6 +146,360 @@ pub const MIN_DURATION_MS: u64 = 100;
 #[allow(dead_code)]
 pub const MAX_DURATION_MS: u64 = 10000;
 
+/// Amber color used for Yellow on RGB pixels.
+///
+/// # Details
+/// Drives WS2812/NeoPixel lamps, where pure red + green looks
+/// too green. Adjust to match the pixel's color balance.
+///
+/// # Value
+/// (255, 191, 0) as (red, green, blue)
+#[allow(dead_code)]
+pub const YELLOW_RGB: (u8, u8, u8) = (255, 191, 0);
+
+/// Duration validation error.
+///
+/// # Details
+/// Describes why a single light duration was rejected.
+/// Carries the offending value for diagnostics.
+///
+/// # Variants
+/// * `TooShort` - Duration below MIN_DURATION_MS
+/// * `TooLong` - Duration above MAX_DURATION_MS
+/// * `YellowNotShortest` - Yellow outlasts red or green in a profile
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum DurationError {
+    TooShort(u64),
+    TooLong(u64),
+    YellowNotShortest,
+}
+
+impl core::fmt::Display for DurationError {
+    /// Formats the error as a human-readable message.
+    ///
+    /// # Details
+    /// Range errors include the rejected value and the violated limit.
+    ///
+    /// # Arguments
+    /// * `f` - Formatter to write into
+    ///
+    /// # Returns
+    /// * `core::fmt::Result` - Result of the write
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DurationError::TooShort(value) => {
+                write!(
+                    f,
+                    "duration {value} ms is below minimum {MIN_DURATION_MS} ms"
+                )
+            }
+            DurationError::TooLong(value) => {
+                write!(
+                    f,
+                    "duration {value} ms exceeds maximum {MAX_DURATION_MS} ms"
+                )
+            }
+            DurationError::YellowNotShortest => {
+                f.write_str("yellow duration must not exceed red or green")
+            }
+        }
+    }
+}
+
+/// Timing configuration error.
+///
+/// # Details
+/// Returned when a timing configuration cannot be applied.
+/// Wraps the underlying duration error or reports a bad phase.
+///
+/// # Variants
+/// * `Duration` - A duration failed range validation
+/// * `InvalidPhase` - A phase index does not name a light
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum ConfigError {
+    Duration(DurationError),
+    InvalidPhase(u8),
+}
+
+impl From<DurationError> for ConfigError {
+    /// Wraps a DurationError in a ConfigError.
+    ///
+    /// # Arguments
+    /// * `err` - Duration error to wrap
+    ///
+    /// # Returns
+    /// * `Self` - ConfigError::Duration variant
+    fn from(err: DurationError) -> Self {
+        ConfigError::Duration(err)
+    }
+}
+
+/// Validates a single light duration.
+///
+/// # Details
+/// Checks the duration lies within MIN_DURATION_MS..=MAX_DURATION_MS.
+/// Usable in const context, so the defaults are checked at compile time.
+///
+/// # Arguments
+/// * `duration` - Duration in milliseconds
+///
+/// # Returns
+/// * `Result<u64, DurationError>` - The duration if valid, error otherwise
+#[allow(dead_code)]
+pub const fn validate_duration(duration: u64) -> Result<u64, DurationError> {
+    if duration < MIN_DURATION_MS {
+        Err(DurationError::TooShort(duration))
+    } else if duration > MAX_DURATION_MS {
+        Err(DurationError::TooLong(duration))
+    } else {
+        Ok(duration)
+    }
+}
+
+/// Validates a full red/yellow/green timing profile.
+///
+/// # Details
+/// Range-checks each duration in red, yellow, green order, then
+/// enforces the safety rule that yellow is no longer than red or
+/// green. Ties are allowed.
+///
+/// # Arguments
+/// * `red` - Red duration in milliseconds
+/// * `yellow` - Yellow duration in milliseconds
+/// * `green` - Green duration in milliseconds
+///
+/// # Returns
+/// * `Result<(), DurationError>` - Ok, or the first check that failed
+#[allow(dead_code)]
+pub fn validate_durations(red: u64, yellow: u64, green: u64) -> Result<(), DurationError> {
+    validate_duration(red)?;
+    validate_duration(yellow)?;
+    validate_duration(green)?;
+    if yellow > red || yellow > green {
+        return Err(DurationError::YellowNotShortest);
+    }
+    Ok(())
+}
+
+// Compile-time checks that the shipped defaults are in range and
+// that yellow is the shortest movement phase. A bad edit to any
+// constant above fails the build instead of the first validation.
+const _: () = assert!(validate_duration(RED_DURATION_MS).is_ok());
+const _: () = assert!(validate_duration(YELLOW_DURATION_MS).is_ok());
+const _: () = assert!(validate_duration(GREEN_DURATION_MS).is_ok());
+const _: () = assert!(validate_duration(ALL_RED_DURATION_MS).is_ok());
+const _: () = assert!(validate_duration(RED_YELLOW_DURATION_MS).is_ok());
+const _: () = assert!(validate_duration(FAULT_FLASH_MS).is_ok());
+const _: () = assert!(validate_duration(NIGHT_FLASH_MS).is_ok());
+const _: () =
+    assert!(YELLOW_DURATION_MS <= RED_DURATION_MS && YELLOW_DURATION_MS <= GREEN_DURATION_MS);
+
+/// Sums a schedule of durations without overflowing.
+///
+/// # Details
+/// Folds with checked_add so long-running schedules cannot wrap
+/// around. An empty slice sums to 0.
+///
+/// # Arguments
+/// * `durations` - Durations in milliseconds
+///
+/// # Returns
+/// * `Option<u64>` - Total in milliseconds, None on overflow
+#[allow(dead_code)]
+pub fn accumulate_durations(durations: &[u64]) -> Option<u64> {
+    durations
+        .iter()
+        .try_fold(0u64, |total, &duration| total.checked_add(duration))
+}
+
+/// Complete set of light durations.
+///
+/// # Details
+/// Groups the durations for every phase so they can be
+/// validated and applied as a single unit.
+///
+/// # Fields
+/// * `red_duration` - Duration for red light in milliseconds
+/// * `yellow_duration` - Duration for yellow light in milliseconds
+/// * `green_duration` - Duration for green light in milliseconds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct TimingPlan {
+    pub red_duration: u64,
+    pub yellow_duration: u64,
+    pub green_duration: u64,
+}
+
+impl Default for TimingPlan {
+    /// Returns the default timing plan.
+    ///
+    /// # Details
+    /// Uses the RED, YELLOW and GREEN duration constants.
+    ///
+    /// # Returns
+    /// * `Self` - TimingPlan with default durations
+    fn default() -> Self {
+        Self {
+            red_duration: RED_DURATION_MS,
+            yellow_duration: YELLOW_DURATION_MS,
+            green_duration: GREEN_DURATION_MS,
+        }
+    }
+}
+
+impl TimingPlan {
+    /// Validates every duration in the plan.
+    ///
+    /// # Details
+    /// Applies validate_durations(), the same checks the builder
+    /// uses: red, yellow and green in range, then yellow no longer
+    /// than red or green. Stops at the first failure.
+    ///
+    /// # Returns
+    /// * `Result<(), ConfigError>` - Ok if the plan is valid
+    #[allow(dead_code)]
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        validate_durations(self.red_duration, self.yellow_duration, self.green_duration)?;
+        Ok(())
+    }
+}
+
+/// Named timing profiles for common intersection types.
+///
+/// # Details
+/// Durations are scaled to fit MIN_DURATION_MS..=MAX_DURATION_MS
+/// and keep yellow the shortest movement phase. Faster roads get
+/// longer greens and longer yellows for the higher approach speed.
+///
+/// # Variants
+/// * `Residential` - Quiet streets: short cycle, red 3000, yellow 800, green 4000
+/// * `Arterial` - Main roads: red 5000, yellow 1200, green 7000
+/// * `Highway` - High-speed approaches: red 8000, yellow 2000, green 10000
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[allow(dead_code)]
+pub enum TimingPreset {
+    Residential,
+    Arterial,
+    Highway,
+}
+
+impl TimingPreset {
+    /// Returns the timing plan for the preset.
+    ///
+    /// # Returns
+    /// * `TimingPlan` - Red, yellow and green durations
+    #[allow(dead_code)]
+    pub const fn plan(self) -> TimingPlan {
+        let (red_duration, yellow_duration, green_duration) = match self {
+            TimingPreset::Residential => (3000, 800, 4000),
+            TimingPreset::Arterial => (5000, 1200, 7000),
+            TimingPreset::Highway => (8000, 2000, 10000),
+        };
+        TimingPlan {
+            red_duration,
+            yellow_duration,
+            green_duration,
+        }
+    }
+}
+
+/// Duration in milliseconds with saturating arithmetic.
+///
+/// # Details
+/// Wraps a u64 so duration math saturates instead of wrapping,
+/// without scattering checked or saturating calls at each site.
+/// Converts to and from u64.
+///
+/// # Fields
+/// * `0` - Duration in milliseconds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[allow(dead_code)]
+pub struct Millis(pub u64);
+
+impl Millis {
+    /// Adds two durations, saturating at u64::MAX.
+    ///
+    /// # Arguments
+    /// * `other` - Duration to add
+    ///
+    /// # Returns
+    /// * `Self` - Sum in milliseconds
+    #[allow(dead_code)]
+    pub const fn saturating_add(self, other: Millis) -> Self {
+        Millis(self.0.saturating_add(other.0))
+    }
+
+    /// Multiplies the duration by a factor, saturating at u64::MAX.
+    ///
+    /// # Arguments
+    /// * `factor` - Multiplier
+    ///
+    /// # Returns
+    /// * `Self` - Product in milliseconds
+    #[allow(dead_code)]
+    pub const fn saturating_mul(self, factor: u64) -> Self {
+        Millis(self.0.saturating_mul(factor))
+    }
+
+    /// Clamps the duration into MIN_DURATION_MS..=MAX_DURATION_MS.
+    ///
+    /// # Returns
+    /// * `Self` - Duration within the valid range
+    #[allow(dead_code)]
+    pub const fn clamp_to_range(self) -> Self {
+        if self.0 < MIN_DURATION_MS {
+            Millis(MIN_DURATION_MS)
+        } else if self.0 > MAX_DURATION_MS {
+            Millis(MAX_DURATION_MS)
+        } else {
+            self
+        }
+    }
+
+    /// Checks the duration lies within the valid range.
+    ///
+    /// # Details
+    /// Delegates to validate_duration().
+    ///
+    /// # Returns
+    /// * `Result<Self, DurationError>` - The duration if valid, error otherwise
+    #[allow(dead_code)]
+    pub const fn validate(self) -> Result<Self, DurationError> {
+        match validate_duration(self.0) {
+            Ok(_) => Ok(self),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl From<u64> for Millis {
+    /// Wraps a millisecond count.
+    ///
+    /// # Arguments
+    /// * `ms` - Duration in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - Wrapped duration
+    fn from(ms: u64) -> Self {
+        Millis(ms)
+    }
+}
+
+impl From<Millis> for u64 {
+    /// Unwraps a millisecond count.
+    ///
+    /// # Arguments
+    /// * `ms` - Wrapped duration
+    ///
+    /// # Returns
+    /// * `u64` - Duration in milliseconds
+    fn from(ms: Millis) -> u64 {
+        ms.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,6 +570,61 @@ mod tests {
         assert!(GREEN_DURATION_MS >= 1000);
     }
 
+    // ==================== ALL_RED_DURATION_MS Tests ====================
+
+    #[test]
+    fn test_all_red_duration_value() {
+        assert_eq!(ALL_RED_DURATION_MS, 1000);
+    }
+
+    #[test]
+    fn test_all_red_within_range() {
+        assert!((MIN_DURATION_MS..=MAX_DURATION_MS).contains(&ALL_RED_DURATION_MS));
+    }
+
+    // ==================== RED_YELLOW_DURATION_MS Tests ====================
+
+    #[test]
+    fn test_red_yellow_duration_value() {
+        assert_eq!(RED_YELLOW_DURATION_MS, 1000);
+    }
+
+    #[test]
+    fn test_red_yellow_within_range() {
+        assert!((MIN_DURATION_MS..=MAX_DURATION_MS).contains(&RED_YELLOW_DURATION_MS));
+    }
+
+    // ==================== FAULT_FLASH_MS Tests ====================
+
+    #[test]
+    fn test_fault_flash_value() {
+        assert_eq!(FAULT_FLASH_MS, 500);
+    }
+
+    #[test]
+    fn test_fault_flash_within_range() {
+        assert!((MIN_DURATION_MS..=MAX_DURATION_MS).contains(&FAULT_FLASH_MS));
+    }
+
+    // ==================== NIGHT_FLASH_MS Tests ====================
+
+    #[test]
+    fn test_night_flash_value() {
+        assert_eq!(NIGHT_FLASH_MS, 500);
+    }
+
+    #[test]
+    fn test_night_flash_within_range() {
+        assert!((MIN_DURATION_MS..=MAX_DURATION_MS).contains(&NIGHT_FLASH_MS));
+    }
+
+    // ==================== BUTTON_DEBOUNCE_MS Tests ====================
+
+    #[test]
+    fn test_button_debounce_value() {
+        assert_eq!(BUTTON_DEBOUNCE_MS, 50);
+    }
+
     // ==================== MIN_DURATION_MS Tests ====================
 
     #[test]
@@ -275,4 +740,326 @@ mod tests {
     fn test_red_equals_green() {
         assert_eq!(RED_DURATION_MS, GREEN_DURATION_MS);
     }
+
+    // ==================== validate_duration Tests ====================
+
+    #[test]
+    fn test_validate_duration_in_range() {
+        assert_eq!(validate_duration(RED_DURATION_MS), Ok(RED_DURATION_MS));
+    }
+
+    #[test]
+    fn test_validate_duration_bounds_inclusive() {
+        assert_eq!(validate_duration(MIN_DURATION_MS), Ok(MIN_DURATION_MS));
+        assert_eq!(validate_duration(MAX_DURATION_MS), Ok(MAX_DURATION_MS));
+    }
+
+    #[test]
+    fn test_validate_duration_too_short() {
+        assert_eq!(
+            validate_duration(MIN_DURATION_MS - 1),
+            Err(DurationError::TooShort(MIN_DURATION_MS - 1))
+        );
+    }
+
+    #[test]
+    fn test_validate_duration_too_long() {
+        assert_eq!(
+            validate_duration(MAX_DURATION_MS + 1),
+            Err(DurationError::TooLong(MAX_DURATION_MS + 1))
+        );
+    }
+
+    #[test]
+    fn test_config_error_from_duration_error() {
+        let err: ConfigError = DurationError::TooShort(0).into();
+        assert_eq!(err, ConfigError::Duration(DurationError::TooShort(0)));
+    }
+
+    // ==================== TimingPlan Tests ====================
+
+    #[test]
+    fn test_timing_plan_default_values() {
+        let plan = TimingPlan::default();
+        assert_eq!(plan.red_duration, RED_DURATION_MS);
+        assert_eq!(plan.yellow_duration, YELLOW_DURATION_MS);
+        assert_eq!(plan.green_duration, GREEN_DURATION_MS);
+    }
+
+    #[test]
+    fn test_timing_plan_default_is_valid() {
+        assert_eq!(TimingPlan::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_timing_plan_invalid_yellow() {
+        let plan = TimingPlan {
+            yellow_duration: 0,
+            ..TimingPlan::default()
+        };
+        assert_eq!(
+            plan.validate(),
+            Err(ConfigError::Duration(DurationError::TooShort(0)))
+        );
+    }
+
+    #[test]
+    fn test_timing_plan_invalid_green() {
+        let plan = TimingPlan {
+            green_duration: MAX_DURATION_MS + 1,
+            ..TimingPlan::default()
+        };
+        assert_eq!(
+            plan.validate(),
+            Err(ConfigError::Duration(DurationError::TooLong(
+                MAX_DURATION_MS + 1
+            )))
+        );
+    }
+
+    #[test]
+    fn test_timing_plan_yellow_not_shortest() {
+        let plan = TimingPlan {
+            red_duration: 5000,
+            yellow_duration: 3000,
+            green_duration: 2000,
+        };
+        assert_eq!(
+            plan.validate(),
+            Err(ConfigError::Duration(DurationError::YellowNotShortest))
+        );
+    }
+
+    // ==================== validate_durations Tests ====================
+
+    #[test]
+    fn test_validate_durations_defaults() {
+        assert_eq!(
+            validate_durations(RED_DURATION_MS, YELLOW_DURATION_MS, GREEN_DURATION_MS),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_validate_durations_yellow_tie_allowed() {
+        assert_eq!(validate_durations(2000, 2000, 2000), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_durations_red_too_short() {
+        assert_eq!(
+            validate_durations(MIN_DURATION_MS - 1, 1000, 3000),
+            Err(DurationError::TooShort(MIN_DURATION_MS - 1))
+        );
+    }
+
+    #[test]
+    fn test_validate_durations_green_too_long() {
+        assert_eq!(
+            validate_durations(3000, 1000, MAX_DURATION_MS + 1),
+            Err(DurationError::TooLong(MAX_DURATION_MS + 1))
+        );
+    }
+
+    #[test]
+    fn test_validate_durations_yellow_longer_than_green() {
+        assert_eq!(
+            validate_durations(3000, 2000, 1500),
+            Err(DurationError::YellowNotShortest)
+        );
+    }
+
+    #[test]
+    fn test_validate_durations_yellow_longer_than_red() {
+        assert_eq!(
+            validate_durations(500, 1000, 3000),
+            Err(DurationError::YellowNotShortest)
+        );
+    }
+
+    #[test]
+    fn test_validate_durations_range_checked_first() {
+        assert_eq!(
+            validate_durations(3000, MAX_DURATION_MS + 1, 3000),
+            Err(DurationError::TooLong(MAX_DURATION_MS + 1))
+        );
+    }
+
+    // ==================== DurationError Tests ====================
+
+    #[test]
+    fn test_duration_error_display_too_short() {
+        assert_eq!(
+            format!("{}", DurationError::TooShort(50)),
+            "duration 50 ms is below minimum 100 ms"
+        );
+    }
+
+    #[test]
+    fn test_duration_error_display_too_long() {
+        assert_eq!(
+            format!("{}", DurationError::TooLong(20000)),
+            "duration 20000 ms exceeds maximum 10000 ms"
+        );
+    }
+
+    #[test]
+    fn test_duration_error_display_yellow_not_shortest() {
+        assert_eq!(
+            format!("{}", DurationError::YellowNotShortest),
+            "yellow duration must not exceed red or green"
+        );
+    }
+
+    #[test]
+    fn test_duration_error_copy() {
+        let err = DurationError::TooShort(1);
+        let copy = err;
+        assert_eq!(err, copy);
+    }
+
+    #[test]
+    fn test_duration_error_partial_eq() {
+        assert_eq!(DurationError::TooLong(5), DurationError::TooLong(5));
+        assert_ne!(DurationError::TooLong(5), DurationError::TooShort(5));
+        assert_ne!(DurationError::TooLong(5), DurationError::TooLong(6));
+    }
+
+    // ==================== YELLOW_RGB Tests ====================
+
+    #[test]
+    fn test_yellow_rgb_value() {
+        assert_eq!(YELLOW_RGB, (255, 191, 0));
+    }
+
+    // ==================== accumulate_durations Tests ====================
+
+    #[test]
+    fn test_accumulate_durations_sum() {
+        let durations = [RED_DURATION_MS, YELLOW_DURATION_MS, GREEN_DURATION_MS];
+        assert_eq!(accumulate_durations(&durations), Some(7000));
+    }
+
+    #[test]
+    fn test_accumulate_durations_empty() {
+        assert_eq!(accumulate_durations(&[]), Some(0));
+    }
+
+    #[test]
+    fn test_accumulate_durations_overflow() {
+        assert_eq!(accumulate_durations(&[u64::MAX, 1]), None);
+    }
+
+    #[test]
+    fn test_accumulate_durations_at_max() {
+        assert_eq!(accumulate_durations(&[u64::MAX - 1, 1]), Some(u64::MAX));
+    }
+
+    // ==================== Const Assertion Tests ====================
+
+    #[test]
+    fn test_const_validate_duration() {
+        const CHECKED: Result<u64, DurationError> = validate_duration(RED_DURATION_MS);
+        assert_eq!(CHECKED, Ok(RED_DURATION_MS));
+    }
+
+    #[test]
+    fn test_shipped_defaults_pass_const_checks() {
+        assert_eq!(
+            validate_durations(RED_DURATION_MS, YELLOW_DURATION_MS, GREEN_DURATION_MS),
+            Ok(())
+        );
+        for duration in [
+            ALL_RED_DURATION_MS,
+            RED_YELLOW_DURATION_MS,
+            FAULT_FLASH_MS,
+            NIGHT_FLASH_MS,
+        ] {
+            assert!(validate_duration(duration).is_ok());
+        }
+    }
+
+    // ==================== Millis Tests ====================
+
+    #[test]
+    fn test_millis_saturating_add() {
+        assert_eq!(Millis(1000).saturating_add(Millis(500)), Millis(1500));
+        assert_eq!(Millis(u64::MAX).saturating_add(Millis(1)), Millis(u64::MAX));
+    }
+
+    #[test]
+    fn test_millis_saturating_mul() {
+        assert_eq!(Millis(1000).saturating_mul(3), Millis(3000));
+        assert_eq!(Millis(u64::MAX).saturating_mul(2), Millis(u64::MAX));
+        assert_eq!(Millis(u64::MAX / 2 + 1).saturating_mul(2), Millis(u64::MAX));
+    }
+
+    #[test]
+    fn test_millis_clamp_below_range() {
+        assert_eq!(Millis(0).clamp_to_range(), Millis(MIN_DURATION_MS));
+    }
+
+    #[test]
+    fn test_millis_clamp_above_range() {
+        assert_eq!(Millis(u64::MAX).clamp_to_range(), Millis(MAX_DURATION_MS));
+    }
+
+    #[test]
+    fn test_millis_clamp_within_range() {
+        assert_eq!(Millis(2500).clamp_to_range(), Millis(2500));
+    }
+
+    #[test]
+    fn test_millis_validate() {
+        assert_eq!(Millis(2500).validate(), Ok(Millis(2500)));
+        assert_eq!(Millis(50).validate(), Err(DurationError::TooShort(50)));
+    }
+
+    #[test]
+    fn test_millis_u64_conversions() {
+        let ms: Millis = 1234.into();
+        assert_eq!(ms, Millis(1234));
+        let raw: u64 = ms.into();
+        assert_eq!(raw, 1234);
+    }
+
+    // ==================== TimingPreset Tests ====================
+
+    #[test]
+    fn test_timing_presets_in_range() {
+        for preset in [
+            TimingPreset::Residential,
+            TimingPreset::Arterial,
+            TimingPreset::Highway,
+        ] {
+            let plan = preset.plan();
+            assert_eq!(plan.validate(), Ok(()));
+            assert_eq!(
+                validate_durations(plan.red_duration, plan.yellow_duration, plan.green_duration),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn test_timing_presets_distinct() {
+        let residential = TimingPreset::Residential.plan();
+        let arterial = TimingPreset::Arterial.plan();
+        let highway = TimingPreset::Highway.plan();
+        assert_ne!(residential, arterial);
+        assert_ne!(arterial, highway);
+        assert_ne!(residential, highway);
+    }
+
+    #[test]
+    fn test_timing_presets_green_grows_with_road_speed() {
+        assert!(
+            TimingPreset::Residential.plan().green_duration
+                < TimingPreset::Arterial.plan().green_duration
+        );
+        assert!(
+            TimingPreset::Arterial.plan().green_duration
+                < TimingPreset::Highway.plan().green_duration
+        );
+    }
 }
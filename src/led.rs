@@ -52,17 +52,314 @@ use embassy_rp::gpio::Output;
 /// # Variants
 /// * `On` - LED is currently on (high)
 /// * `Off` - LED is currently off (low)
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(dead_code)]
 pub enum LedState {
     On,
     Off,
 }
 
+impl Default for LedState {
+    /// Returns the default LED state.
+    ///
+    /// # Details
+    /// Off, matching an LED before it is driven.
+    ///
+    /// # Returns
+    /// * `Self` - LedState::Off
+    fn default() -> Self {
+        LedState::Off
+    }
+}
+
+impl core::fmt::Display for LedState {
+    /// Formats the state as an upper-case name.
+    ///
+    /// # Details
+    /// Produces "ON" or "OFF" for log output.
+    ///
+    /// # Arguments
+    /// * `f` - Formatter to write into
+    ///
+    /// # Returns
+    /// * `core::fmt::Result` - Result of the write
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let name = match self {
+            LedState::On => "ON",
+            LedState::Off => "OFF",
+        };
+        f.write_str(name)
+    }
+}
+
+impl From<bool> for LedState {
+    /// Converts boolean to LedState.
+    ///
+    /// # Details
+    /// Maps true to On, false to Off.
+    ///
+    /// # Arguments
+    /// * `state` - Boolean state to convert
+    ///
+    /// # Returns
+    /// * `Self` - On if true, Off if false
+    fn from(state: bool) -> Self {
+        if state { LedState::On } else { LedState::Off }
+    }
+}
+
+impl core::ops::Not for LedState {
+    type Output = Self;
+
+    /// Inverts the LED state.
+    ///
+    /// # Details
+    /// Maps On to Off and Off to On, so `!state` toggles an LED.
+    ///
+    /// # Returns
+    /// * `Self` - Inverted state
+    fn not(self) -> Self {
+        match self {
+            LedState::On => LedState::Off,
+            LedState::Off => LedState::On,
+        }
+    }
+}
+
+impl From<LedState> for bool {
+    /// Converts LedState to boolean for GPIO control.
+    ///
+    /// # Details
+    /// Maps On state to true (high), Off state to false (low).
+    ///
+    /// # Arguments
+    /// * `state` - LED state to convert
+    ///
+    /// # Returns
+    /// * `Self` - true for On, false for Off
+    fn from(state: LedState) -> Self {
+        matches!(state, LedState::On)
+    }
+}
+
+/// PWM counter top value for LED channels.
+///
+/// # Details
+/// Full-scale compare value for the embassy-rp PWM slice.
+/// A compare of PWM_TOP keeps the output high for the whole period.
+///
+/// # Value
+/// 0xFFFF (full 16-bit range)
+#[allow(dead_code)]
+pub const PWM_TOP: u16 = 0xFFFF;
+
+/// LED brightness level enumeration.
+///
+/// # Details
+/// PWM-capable counterpart to LedState for boards that drive
+/// the lamps through PWM channels.
+///
+/// # Variants
+/// * `Off` - LED fully off (0% duty)
+/// * `On` - LED fully on (100% duty)
+/// * `Dimmed` - Partial brightness as a 0-255 duty value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum LedLevel {
+    Off,
+    On,
+    Dimmed(u8),
+}
+
+/// Converts LedLevel to a PWM compare value.
+///
+/// # Details
+/// Scales the 0-255 brightness linearly onto 0..=PWM_TOP.
+/// Off maps to 0 and On maps to PWM_TOP.
+///
+/// # Arguments
+/// * `level` - Brightness level to convert
+///
+/// # Returns
+/// * `u16` - PWM compare value
+#[allow(dead_code)]
+pub fn led_level_to_duty(level: LedLevel) -> u16 {
+    match level {
+        LedLevel::Off => 0,
+        LedLevel::On => PWM_TOP,
+        LedLevel::Dimmed(value) => (value as u32 * PWM_TOP as u32 / u8::MAX as u32) as u16,
+    }
+}
+
+/// Produces a linear brightness ramp between two duty values.
+///
+/// # Details
+/// Yields exactly `steps` values; the first is `from` and the
+/// last is `to`. Works for ascending, descending and constant
+/// ramps. A single step yields `to`; zero steps yields nothing.
+///
+/// # Arguments
+/// * `from` - Starting brightness (0-255)
+/// * `to` - Final brightness (0-255)
+/// * `steps` - Number of values to produce
+///
+/// # Returns
+/// * `impl Iterator<Item = u8>` - Brightness values along the ramp
+#[allow(dead_code)]
+pub fn fade_steps(from: u8, to: u8, steps: u16) -> impl Iterator<Item = u8> {
+    let span = to as i32 - from as i32;
+    let last = steps.saturating_sub(1) as i32;
+    (0..steps).map(move |i| {
+        if last == 0 {
+            to
+        } else {
+            (from as i32 + span * i as i32 / last) as u8
+        }
+    })
+}
+
+/// Generates an on/off blink pattern.
+///
+/// # Details
+/// Yields `(level, duration)` pairs starting with on, alternating
+/// on and off, for `cycles` full on/off cycles. Zero cycles
+/// yields nothing.
+///
+/// # Arguments
+/// * `on_ms` - On time per cycle in milliseconds
+/// * `off_ms` - Off time per cycle in milliseconds
+/// * `cycles` - Number of on/off cycles
+///
+/// # Returns
+/// * `impl Iterator<Item = (bool, u64)>` - Level and hold time pairs
+#[allow(dead_code)]
+pub fn blink_pattern(on_ms: u64, off_ms: u64, cycles: u32) -> impl Iterator<Item = (bool, u64)> {
+    (0..cycles as u64 * 2).map(move |step| {
+        if step % 2 == 0 {
+            (true, on_ms)
+        } else {
+            (false, off_ms)
+        }
+    })
+}
+
+/// Maximum number of steps held by a Pattern.
+///
+/// # Details
+/// Fits SOS (18 steps) or a 16-cycle blink while keeping a
+/// Pattern at about half a kilobyte.
+///
+/// # Value
+/// 32 steps
+#[allow(dead_code)]
+pub const PATTERN_CAPACITY: usize = 32;
+
+/// Declarative LED sequence of timed steps.
+///
+/// # Details
+/// Each step holds an LedState for a duration in milliseconds.
+/// Steps are stored inline, up to PATTERN_CAPACITY, so patterns
+/// can be built and played without allocation.
+///
+/// # Fields
+/// * `steps` - (state, duration_ms) steps in playback order
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Pattern {
+    steps: heapless::Vec<(LedState, u64), PATTERN_CAPACITY>,
+}
+
+impl Pattern {
+    /// Creates a pattern from a slice of steps.
+    ///
+    /// # Arguments
+    /// * `steps` - (state, duration_ms) steps in playback order
+    ///
+    /// # Returns
+    /// * `Option<Self>` - Pattern, or None if longer than PATTERN_CAPACITY
+    #[allow(dead_code)]
+    pub fn new(steps: &[(LedState, u64)]) -> Option<Self> {
+        heapless::Vec::from_slice(steps)
+            .ok()
+            .map(|steps| Self { steps })
+    }
+
+    /// Creates an on/off blink pattern.
+    ///
+    /// # Details
+    /// Follows blink_pattern(): starts on and alternates for
+    /// `cycles` full on/off cycles.
+    ///
+    /// # Arguments
+    /// * `on_ms` - On time per cycle in milliseconds
+    /// * `off_ms` - Off time per cycle in milliseconds
+    /// * `cycles` - Number of on/off cycles
+    ///
+    /// # Returns
+    /// * `Option<Self>` - Pattern, or None if the steps exceed PATTERN_CAPACITY
+    #[allow(dead_code)]
+    pub fn blink(on_ms: u64, off_ms: u64, cycles: u32) -> Option<Self> {
+        let mut steps = heapless::Vec::new();
+        for (level, duration) in blink_pattern(on_ms, off_ms, cycles) {
+            steps.push((LedState::from(level), duration)).ok()?;
+        }
+        Some(Self { steps })
+    }
+
+    /// Creates the Morse SOS distress pattern.
+    ///
+    /// # Details
+    /// Three dots, three dashes, three dots. A dot is on for one
+    /// unit and a dash for three; symbols are separated by one
+    /// unit off and the pattern ends with a seven-unit word gap.
+    ///
+    /// # Arguments
+    /// * `unit_ms` - Morse time unit in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - 18-step SOS pattern
+    #[allow(dead_code)]
+    pub fn sos(unit_ms: u64) -> Self {
+        let mut steps = heapless::Vec::new();
+        for symbol in 0..9 {
+            let on = if (3..6).contains(&symbol) { 3 } else { 1 };
+            let off = if symbol == 8 { 7 } else { 1 };
+            let _ = steps.push((LedState::On, unit_ms.saturating_mul(on)));
+            let _ = steps.push((LedState::Off, unit_ms.saturating_mul(off)));
+        }
+        Self { steps }
+    }
+
+    /// Returns the steps in playback order.
+    ///
+    /// # Returns
+    /// * `impl Iterator<Item = (LedState, u64)>` - (state, duration_ms) steps
+    #[allow(dead_code)]
+    pub fn steps(&self) -> impl Iterator<Item = (LedState, u64)> + '_ {
+        self.steps.iter().copied()
+    }
+
+    /// Returns the time to play the whole pattern once.
+    ///
+    /// # Details
+    /// Sums every step duration, saturating at u64::MAX.
+    ///
+    /// # Returns
+    /// * `u64` - Total duration in milliseconds
+    #[allow(dead_code)]
+    pub fn total_duration(&self) -> u64 {
+        self.steps()
+            .fold(0u64, |total, (_, duration)| total.saturating_add(duration))
+    }
+}
+
 /// Converts boolean to LedState.
 ///
 /// # Details
 /// Maps true to On, false to Off.
+/// Thin wrapper over the From<bool> implementation.
 ///
 /// # Arguments
 /// * `state` - Boolean state to convert
@@ -71,13 +368,14 @@ pub enum LedState {
 /// * `LedState` - On if true, Off if false
 #[allow(dead_code)]
 pub fn bool_to_led_state(state: bool) -> LedState {
-    if state { LedState::On } else { LedState::Off }
+    LedState::from(state)
 }
 
 /// Converts LedState to boolean for GPIO control.
 ///
 /// # Details
 /// Maps On state to true (high), Off state to false (low).
+/// Thin wrapper over the From<LedState> implementation.
 ///
 /// # Arguments
 /// * `state` - LED state to convert
@@ -86,7 +384,7 @@ pub fn bool_to_led_state(state: bool) -> LedState {
 /// * `bool` - true for On, false for Off
 #[allow(dead_code)]
 pub fn led_state_to_bool(state: LedState) -> bool {
-    matches!(state, LedState::On)
+    bool::from(state)
 }
 
 /// Determines LED output level from boolean state.
@@ -109,6 +407,7 @@ pub fn get_led_level(state: bool) -> bool {
 ///
 /// # Details
 /// Toggles LED state from On to Off or Off to On.
+/// Thin wrapper over the Not implementation.
 ///
 /// # Arguments
 /// * `state` - Current LED state
@@ -117,10 +416,7 @@ pub fn get_led_level(state: bool) -> bool {
 /// * `LedState` - Inverted state
 #[allow(dead_code)]
 pub fn invert_led_state(state: LedState) -> LedState {
-    match state {
-        LedState::On => LedState::Off,
-        LedState::Off => LedState::On,
-    }
+    !state
 }
 
 /// Inverts boolean LED state.
@@ -138,6 +434,65 @@ pub fn invert_bool_state(state: bool) -> bool {
     !state
 }
 
+/// Stateful LED wrapper.
+///
+/// # Details
+/// Owns an LedState so callers can toggle and set an LED
+/// without tracking the enum themselves.
+///
+/// # Fields
+/// * `state` - Current LED state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Led {
+    state: LedState,
+}
+
+impl Led {
+    /// Creates an LED in the given state.
+    ///
+    /// # Arguments
+    /// * `state` - Initial LED state
+    ///
+    /// # Returns
+    /// * `Self` - New Led instance
+    #[allow(dead_code)]
+    pub const fn new(state: LedState) -> Self {
+        Self { state }
+    }
+
+    /// Toggles the LED and returns the new state.
+    ///
+    /// # Details
+    /// Uses invert_led_state() to flip On and Off.
+    ///
+    /// # Returns
+    /// * `LedState` - State after toggling
+    #[allow(dead_code)]
+    pub fn toggle(&mut self) -> LedState {
+        self.state = invert_led_state(self.state);
+        self.state
+    }
+
+    /// Returns the current LED state.
+    ///
+    /// # Returns
+    /// * `LedState` - Current state
+    #[allow(dead_code)]
+    pub fn state(&self) -> LedState {
+        self.state
+    }
+
+    /// Sets the LED state.
+    ///
+    /// # Arguments
+    /// * `state` - New LED state
+    #[allow(dead_code)]
+    pub fn set(&mut self, state: LedState) {
+        self.state = state;
+    }
+}
+
 /// Sets LED GPIO output based on boolean state.
 ///
 /// # Details
@@ -485,4 +840,370 @@ mod tests {
         assert_eq!(state1, state2);
         assert_eq!(state2, state1);
     }
+
+    // ==================== Display Tests ====================
+
+    #[test]
+    fn test_led_state_display_on() {
+        assert_eq!(format!("{}", LedState::On), "ON");
+    }
+
+    #[test]
+    fn test_led_state_display_off() {
+        assert_eq!(format!("{}", LedState::Off), "OFF");
+    }
+
+    // ==================== LedLevel Tests ====================
+
+    #[test]
+    fn test_led_level_to_duty_off() {
+        assert_eq!(led_level_to_duty(LedLevel::Off), 0);
+    }
+
+    #[test]
+    fn test_led_level_to_duty_on() {
+        assert_eq!(led_level_to_duty(LedLevel::On), PWM_TOP);
+    }
+
+    #[test]
+    fn test_led_level_to_duty_dimmed_zero() {
+        assert_eq!(led_level_to_duty(LedLevel::Dimmed(0)), 0);
+    }
+
+    #[test]
+    fn test_led_level_to_duty_dimmed_full() {
+        assert_eq!(led_level_to_duty(LedLevel::Dimmed(255)), PWM_TOP);
+    }
+
+    #[test]
+    fn test_led_level_to_duty_dimmed_mid() {
+        assert_eq!(led_level_to_duty(LedLevel::Dimmed(128)), 128 * 257);
+    }
+
+    #[test]
+    fn test_led_level_to_duty_monotonic() {
+        let mut last = 0;
+        for value in 0..=u8::MAX {
+            let duty = led_level_to_duty(LedLevel::Dimmed(value));
+            assert!(duty >= last);
+            last = duty;
+        }
+    }
+
+    // ==================== fade_steps Tests ====================
+
+    #[test]
+    fn test_fade_steps_first_and_last() {
+        let ramp: Vec<u8> = fade_steps(0, 255, 10).collect();
+        assert_eq!(ramp.first(), Some(&0));
+        assert_eq!(ramp.last(), Some(&255));
+    }
+
+    #[test]
+    fn test_fade_steps_count() {
+        assert_eq!(fade_steps(10, 200, 17).count(), 17);
+    }
+
+    #[test]
+    fn test_fade_steps_linear_values() {
+        let ramp: Vec<u8> = fade_steps(0, 100, 5).collect();
+        assert_eq!(ramp, [0, 25, 50, 75, 100]);
+    }
+
+    #[test]
+    fn test_fade_steps_increasing() {
+        let ramp: Vec<u8> = fade_steps(3, 250, 64).collect();
+        assert!(ramp.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_fade_steps_decreasing() {
+        let ramp: Vec<u8> = fade_steps(255, 0, 64).collect();
+        assert_eq!(ramp.first(), Some(&255));
+        assert_eq!(ramp.last(), Some(&0));
+        assert!(ramp.windows(2).all(|w| w[0] >= w[1]));
+    }
+
+    #[test]
+    fn test_fade_steps_constant() {
+        assert!(fade_steps(42, 42, 8).all(|value| value == 42));
+    }
+
+    #[test]
+    fn test_fade_steps_single_step() {
+        let ramp: Vec<u8> = fade_steps(0, 200, 1).collect();
+        assert_eq!(ramp, [200]);
+    }
+
+    #[test]
+    fn test_fade_steps_zero_steps() {
+        assert_eq!(fade_steps(0, 200, 0).count(), 0);
+    }
+
+    // ==================== From Conversion Tests ====================
+
+    #[test]
+    fn test_from_bool_true() {
+        assert_eq!(LedState::from(true), LedState::On);
+    }
+
+    #[test]
+    fn test_from_bool_false() {
+        assert_eq!(LedState::from(false), LedState::Off);
+    }
+
+    #[test]
+    fn test_into_led_state() {
+        let pin_high = true;
+        let state: LedState = pin_high.into();
+        assert_eq!(state, LedState::On);
+    }
+
+    #[test]
+    fn test_from_led_state_on() {
+        assert!(bool::from(LedState::On));
+    }
+
+    #[test]
+    fn test_into_bool_off() {
+        let level: bool = LedState::Off.into();
+        assert!(!level);
+    }
+
+    #[test]
+    fn test_from_agrees_with_free_functions() {
+        for value in [true, false] {
+            assert_eq!(LedState::from(value), bool_to_led_state(value));
+        }
+        for state in [LedState::On, LedState::Off] {
+            assert_eq!(bool::from(state), led_state_to_bool(state));
+        }
+    }
+
+    // ==================== Serde Tests ====================
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_led_state_round_trip() {
+        for state in [LedState::On, LedState::Off] {
+            let json = serde_json::to_string(&state).unwrap();
+            assert_eq!(serde_json::from_str::<LedState>(&json).unwrap(), state);
+        }
+    }
+
+    // ==================== defmt Tests ====================
+
+    #[cfg(feature = "defmt")]
+    #[test]
+    fn test_defmt_format_implemented() {
+        fn assert_format<T: defmt::Format>() {}
+        assert_format::<LedState>();
+    }
+
+    // ==================== blink_pattern Tests ====================
+
+    #[test]
+    fn test_blink_pattern_length() {
+        assert_eq!(blink_pattern(200, 300, 5).count(), 10);
+    }
+
+    #[test]
+    fn test_blink_pattern_zero_cycles() {
+        assert_eq!(blink_pattern(200, 300, 0).count(), 0);
+    }
+
+    #[test]
+    fn test_blink_pattern_alternates() {
+        let pattern: Vec<_> = blink_pattern(200, 300, 2).collect();
+        assert_eq!(
+            pattern,
+            [(true, 200), (false, 300), (true, 200), (false, 300)]
+        );
+    }
+
+    #[test]
+    fn test_blink_pattern_equal_times() {
+        let pattern: Vec<_> = blink_pattern(500, 500, 3).collect();
+        assert!(pattern.iter().all(|&(_, ms)| ms == 500));
+        assert!(pattern.windows(2).all(|w| w[0].0 != w[1].0));
+    }
+
+    #[test]
+    fn test_blink_pattern_max_cycles_does_not_overflow() {
+        assert_eq!(
+            blink_pattern(1, 1, u32::MAX).size_hint().0,
+            u32::MAX as usize * 2
+        );
+    }
+
+    // ==================== Led Tests ====================
+
+    #[test]
+    fn test_led_new_state() {
+        assert_eq!(Led::new(LedState::On).state(), LedState::On);
+        assert_eq!(Led::new(LedState::Off).state(), LedState::Off);
+    }
+
+    #[test]
+    fn test_led_toggle_from_on() {
+        let mut led = Led::new(LedState::On);
+        assert_eq!(led.toggle(), LedState::Off);
+        assert_eq!(led.state(), LedState::Off);
+    }
+
+    #[test]
+    fn test_led_toggle_from_off() {
+        let mut led = Led::new(LedState::Off);
+        assert_eq!(led.toggle(), LedState::On);
+        assert_eq!(led.state(), LedState::On);
+    }
+
+    #[test]
+    fn test_led_double_toggle_restores() {
+        let mut led = Led::new(LedState::On);
+        led.toggle();
+        led.toggle();
+        assert_eq!(led.state(), LedState::On);
+    }
+
+    #[test]
+    fn test_led_set_overrides() {
+        let mut led = Led::new(LedState::Off);
+        led.set(LedState::On);
+        assert_eq!(led.state(), LedState::On);
+        led.set(LedState::On);
+        assert_eq!(led.state(), LedState::On);
+        led.set(LedState::Off);
+        assert_eq!(led.state(), LedState::Off);
+    }
+
+    // ==================== Hash Tests ====================
+
+    #[test]
+    fn test_led_state_as_map_key() {
+        let mut map: heapless::FnvIndexMap<LedState, u32, 2> = heapless::FnvIndexMap::new();
+        map.insert(LedState::On, 10).unwrap();
+        map.insert(LedState::Off, 20).unwrap();
+        assert_eq!(map.get(&LedState::On), Some(&10));
+        assert_eq!(map.get(&LedState::Off), Some(&20));
+    }
+
+    // ==================== Default Tests ====================
+
+    #[test]
+    fn test_led_state_default_is_off() {
+        assert_eq!(LedState::default(), LedState::Off);
+    }
+
+    #[test]
+    fn test_led_state_default_in_derived_struct() {
+        #[derive(Default)]
+        struct Holder {
+            state: LedState,
+        }
+        assert_eq!(Holder::default().state, LedState::Off);
+    }
+
+    // ==================== Pattern Tests ====================
+
+    #[test]
+    fn test_pattern_custom_steps() {
+        let pattern = Pattern::new(&[
+            (LedState::On, 100),
+            (LedState::Off, 50),
+            (LedState::On, 200),
+        ])
+        .unwrap();
+        let steps: std::vec::Vec<_> = pattern.steps().collect();
+        assert_eq!(
+            steps,
+            [
+                (LedState::On, 100),
+                (LedState::Off, 50),
+                (LedState::On, 200)
+            ]
+        );
+        assert_eq!(pattern.total_duration(), 350);
+    }
+
+    #[test]
+    fn test_pattern_empty() {
+        let pattern = Pattern::new(&[]).unwrap();
+        assert_eq!(pattern.steps().count(), 0);
+        assert_eq!(pattern.total_duration(), 0);
+    }
+
+    #[test]
+    fn test_pattern_too_long() {
+        let steps = [(LedState::On, 1); PATTERN_CAPACITY + 1];
+        assert!(Pattern::new(&steps).is_none());
+        assert!(Pattern::new(&steps[..PATTERN_CAPACITY]).is_some());
+    }
+
+    #[test]
+    fn test_pattern_blink() {
+        let pattern = Pattern::blink(300, 100, 2).unwrap();
+        let steps: std::vec::Vec<_> = pattern.steps().collect();
+        assert_eq!(
+            steps,
+            [
+                (LedState::On, 300),
+                (LedState::Off, 100),
+                (LedState::On, 300),
+                (LedState::Off, 100)
+            ]
+        );
+        assert_eq!(pattern.total_duration(), 800);
+    }
+
+    #[test]
+    fn test_pattern_blink_over_capacity() {
+        assert!(Pattern::blink(1, 1, PATTERN_CAPACITY as u32 / 2).is_some());
+        assert!(Pattern::blink(1, 1, PATTERN_CAPACITY as u32 / 2 + 1).is_none());
+    }
+
+    #[test]
+    fn test_pattern_sos() {
+        let pattern = Pattern::sos(100);
+        assert_eq!(pattern.steps().count(), 18);
+        let on: std::vec::Vec<u64> = pattern
+            .steps()
+            .filter(|(state, _)| *state == LedState::On)
+            .map(|(_, duration)| duration)
+            .collect();
+        assert_eq!(on, [100, 100, 100, 300, 300, 300, 100, 100, 100]);
+        assert_eq!(pattern.total_duration(), (9 + 6 + 8 + 7) * 100);
+    }
+
+    #[test]
+    fn test_pattern_total_saturates() {
+        let pattern = Pattern::new(&[(LedState::On, u64::MAX), (LedState::Off, 1)]).unwrap();
+        assert_eq!(pattern.total_duration(), u64::MAX);
+    }
+
+    // ==================== Not Tests ====================
+
+    #[test]
+    fn test_not_on_is_off() {
+        assert_eq!(!LedState::On, LedState::Off);
+    }
+
+    #[test]
+    fn test_not_off_is_on() {
+        assert_eq!(!LedState::Off, LedState::On);
+    }
+
+    #[test]
+    fn test_not_double_negation() {
+        for state in [LedState::On, LedState::Off] {
+            assert_eq!(!!state, state);
+        }
+    }
+
+    #[test]
+    fn test_not_matches_invert_led_state() {
+        for state in [LedState::On, LedState::Off] {
+            assert_eq!(!state, invert_led_state(state));
+        }
+    }
 }
@@ -50,10 +50,8 @@ mod traffic_light;
 
 use embassy_executor::Spawner;
 use embassy_rp::gpio::{Level, Output};
-use embassy_time::Timer;
-use led::set_led;
 use panic_halt as _;
-use traffic_light::TrafficLightController;
+use traffic_light::{TrafficLightController, TrafficLights, run_traffic_light};
 
 /// Main application entry point.
 ///
@@ -70,15 +68,11 @@ use traffic_light::TrafficLightController;
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) {
     let p = embassy_rp::init(Default::default());
-    let mut red = Output::new(p.PIN_16, Level::Low);
-    let mut yellow = Output::new(p.PIN_17, Level::Low);
-    let mut green = Output::new(p.PIN_18, Level::Low);
+    let mut lights = TrafficLights::new(
+        Output::new(p.PIN_16, Level::Low),
+        Output::new(p.PIN_17, Level::Low),
+        Output::new(p.PIN_18, Level::Low),
+    );
     let mut controller = TrafficLightController::new();
-    loop {
-        set_led(&mut red, controller.is_red());
-        set_led(&mut yellow, controller.is_yellow());
-        set_led(&mut green, controller.is_green());
-        Timer::after_millis(controller.current_duration()).await;
-        controller.advance();
-    }
+    run_traffic_light(&mut controller, &mut lights).await
 }
@@ -40,7 +40,23 @@
 //! CREATION DATE: December 7, 2025
 //! UPDATE DATE: December 7, 2025
 
-use crate::config::{GREEN_DURATION_MS, RED_DURATION_MS, YELLOW_DURATION_MS};
+#[cfg(all(feature = "embassy-rp", feature = "embassy-time"))]
+use crate::config::BUTTON_DEBOUNCE_MS;
+use crate::config::{
+    ALL_RED_DURATION_MS, ConfigError, DurationError, FAULT_FLASH_MS, GREEN_DURATION_MS,
+    MAX_DURATION_MS, MIN_DURATION_MS, Millis, NIGHT_FLASH_MS, RED_DURATION_MS,
+    RED_YELLOW_DURATION_MS, TimingPlan, TimingPreset, YELLOW_DURATION_MS, YELLOW_RGB,
+    accumulate_durations, validate_duration, validate_durations,
+};
+#[cfg(feature = "embassy-rp")]
+use crate::led::set_led;
+use crate::led::{LedLevel, led_level_to_duty};
+#[cfg(all(feature = "embassy-rp", feature = "embassy-time"))]
+use embassy_rp::gpio::Input;
+#[cfg(feature = "embassy-rp")]
+use embassy_rp::gpio::Output;
+#[cfg(all(feature = "embassy-rp", feature = "embassy-time"))]
+use embassy_time::{Instant, Timer};
 
 /// Traffic light state enumeration.
 ///
@@ -52,12 +68,381 @@ use crate::config::{GREEN_DURATION_MS, RED_DURATION_MS, YELLOW_DURATION_MS};
 /// * `Red` - Stop signal (red LED on)
 /// * `Yellow` - Caution signal (yellow LED on)
 /// * `Green` - Go signal (green LED on)
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// * `AllRed` - Clearance interval between Yellow and Red (red LED on)
+/// * `RedYellow` - Get-ready interval between Red and Green (red and yellow LEDs on)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(dead_code)]
 pub enum TrafficLightState {
     Red,
     Yellow,
     Green,
+    AllRed,
+    RedYellow,
+}
+
+impl Default for TrafficLightState {
+    /// Returns the default traffic light state.
+    ///
+    /// # Details
+    /// Red, matching the controller's starting state.
+    ///
+    /// # Returns
+    /// * `Self` - TrafficLightState::Red
+    fn default() -> Self {
+        TrafficLightState::Red
+    }
+}
+
+impl core::fmt::Display for TrafficLightState {
+    /// Formats the state as an upper-case name.
+    ///
+    /// # Details
+    /// Produces "RED", "YELLOW", "GREEN", "ALL_RED" or "RED_YELLOW"
+    /// for log output.
+    ///
+    /// # Arguments
+    /// * `f` - Formatter to write into
+    ///
+    /// # Returns
+    /// * `core::fmt::Result` - Result of the write
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl From<TrafficLightState> for u8 {
+    /// Converts a state to its wire code.
+    ///
+    /// # Details
+    /// Codes: 0 Red, 1 Yellow, 2 Green, 3 AllRed, 4 RedYellow.
+    ///
+    /// # Arguments
+    /// * `state` - Traffic light state
+    ///
+    /// # Returns
+    /// * `u8` - State code
+    fn from(state: TrafficLightState) -> u8 {
+        match state {
+            TrafficLightState::Red => 0,
+            TrafficLightState::Yellow => 1,
+            TrafficLightState::Green => 2,
+            TrafficLightState::AllRed => 3,
+            TrafficLightState::RedYellow => 4,
+        }
+    }
+}
+
+impl TryFrom<u8> for TrafficLightState {
+    type Error = InvalidStateCode;
+
+    /// Parses a state from its wire code.
+    ///
+    /// # Details
+    /// Inverse of From<TrafficLightState> for u8, for bytes read
+    /// from UART or other untrusted links.
+    ///
+    /// # Arguments
+    /// * `code` - State code (0 Red, 1 Yellow, 2 Green, 3 AllRed, 4 RedYellow)
+    ///
+    /// # Returns
+    /// * `Result<Self, InvalidStateCode>` - State, or the rejected code
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        match code {
+            0 => Ok(TrafficLightState::Red),
+            1 => Ok(TrafficLightState::Yellow),
+            2 => Ok(TrafficLightState::Green),
+            3 => Ok(TrafficLightState::AllRed),
+            4 => Ok(TrafficLightState::RedYellow),
+            _ => Err(InvalidStateCode(code)),
+        }
+    }
+}
+
+/// Error for a byte that is not a valid state code.
+///
+/// # Fields
+/// * `0` - The rejected code
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct InvalidStateCode(pub u8);
+
+impl core::fmt::Display for InvalidStateCode {
+    /// Formats the error as a human-readable message.
+    ///
+    /// # Arguments
+    /// * `f` - Formatter to write into
+    ///
+    /// # Returns
+    /// * `core::fmt::Result` - Result of the write
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid state code {}", self.0)
+    }
+}
+
+impl TrafficLightState {
+    /// Parses a state from its name, ignoring ASCII case.
+    ///
+    /// # Details
+    /// Accepts "red", "yellow" and "green" for text config, plus
+    /// "all_red" and "red_yellow" so every Display name parses back.
+    ///
+    /// # Arguments
+    /// * `s` - State name
+    ///
+    /// # Returns
+    /// * `Result<Self, ParseStateError>` - State, or error for an unknown name
+    #[allow(dead_code)]
+    pub fn from_name(s: &str) -> Result<Self, ParseStateError> {
+        [
+            TrafficLightState::Red,
+            TrafficLightState::Yellow,
+            TrafficLightState::Green,
+            TrafficLightState::AllRed,
+            TrafficLightState::RedYellow,
+        ]
+        .into_iter()
+        .find(|state| state.name().eq_ignore_ascii_case(s))
+        .ok_or(ParseStateError)
+    }
+
+    /// Returns the state the perpendicular street should show.
+    ///
+    /// # Details
+    /// Quick crossroads model without an Intersection: the cross
+    /// street gets Green only while this street is held at Red.
+    /// It stays at Red during Yellow, the AllRed clearance and the
+    /// RedYellow get-ready phase, so the two are never both Green.
+    ///
+    /// # Returns
+    /// * `TrafficLightState` - Complementary cross-street state
+    #[allow(dead_code)]
+    pub fn complement(self) -> TrafficLightState {
+        match self {
+            TrafficLightState::Red => TrafficLightState::Green,
+            TrafficLightState::Yellow
+            | TrafficLightState::Green
+            | TrafficLightState::AllRed
+            | TrafficLightState::RedYellow => TrafficLightState::Red,
+        }
+    }
+
+    /// Returns the upper-case name used by Display.
+    ///
+    /// # Returns
+    /// * `&'static str` - "RED", "YELLOW", "GREEN", "ALL_RED" or "RED_YELLOW"
+    fn name(self) -> &'static str {
+        match self {
+            TrafficLightState::Red => "RED",
+            TrafficLightState::Yellow => "YELLOW",
+            TrafficLightState::Green => "GREEN",
+            TrafficLightState::AllRed => "ALL_RED",
+            TrafficLightState::RedYellow => "RED_YELLOW",
+        }
+    }
+}
+
+/// Error for a string that does not name a state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct ParseStateError;
+
+impl core::fmt::Display for ParseStateError {
+    /// Formats the error as a human-readable message.
+    ///
+    /// # Arguments
+    /// * `f` - Formatter to write into
+    ///
+    /// # Returns
+    /// * `core::fmt::Result` - Result of the write
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("unrecognized traffic light state name")
+    }
+}
+
+/// Seconds in one day, used to wrap times of day at midnight.
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Number of low bits holding the state code in a packed word.
+const PACKED_STATE_BITS: u32 = 8;
+
+/// Mask selecting the state code from a packed word.
+const PACKED_STATE_MASK: u64 = (1 << PACKED_STATE_BITS) - 1;
+
+/// Maximum number of entries returned by schedule().
+///
+/// # Details
+/// Holds 16 full cycles of three phases, or 12 cycles with the
+/// all-red clearance enabled, while keeping the table at 768 bytes.
+///
+/// # Value
+/// 48 entries
+#[allow(dead_code)]
+pub const SCHEDULE_CAPACITY: usize = 48;
+
+/// Base state-machine transitions as (from, to) pairs.
+///
+/// # Details
+/// The normal cycle Red -> Green -> Yellow -> Red as data, so
+/// the FSM can be audited. next_state() reads this table and
+/// advance() delegates to it; the optional AllRed and RedYellow
+/// phases are inserted inside the Yellow -> Red and Red -> Green
+/// edges.
+///
+/// # Value
+/// Red -> Green, Green -> Yellow, Yellow -> Red
+#[allow(dead_code)]
+pub const TRANSITIONS: [(TrafficLightState, TrafficLightState); 3] = [
+    (TrafficLightState::Red, TrafficLightState::Green),
+    (TrafficLightState::Green, TrafficLightState::Yellow),
+    (TrafficLightState::Yellow, TrafficLightState::Red),
+];
+
+/// Mask selecting the state code from a status byte.
+const STATUS_STATE_MASK: u8 = 0b0000_0011;
+
+/// Status byte bit set while in fault mode.
+const STATUS_FAULT_BIT: u8 = 1 << 2;
+
+/// Status byte bit set while in night mode.
+const STATUS_NIGHT_BIT: u8 = 1 << 3;
+
+/// Status byte bit set while preempted.
+const STATUS_PREEMPT_BIT: u8 = 1 << 4;
+
+/// Status byte bit set during RedYellow, sent with the Red code.
+const STATUS_RED_YELLOW_BIT: u8 = 1 << 6;
+
+/// Status byte bits that must be clear.
+const STATUS_RESERVED_MASK: u8 = 0b1010_0000;
+
+/// Pedestrian signal enumeration.
+///
+/// # Details
+/// Companion signal for a pedestrian crossing.
+/// Derived from the main traffic light state.
+///
+/// # Variants
+/// * `Walk` - Pedestrians may cross (white LED on)
+/// * `DontWalk` - Pedestrians must wait (orange LED on)
+/// * `FlashingDontWalk` - Red is ending, do not start crossing (orange LED flashing)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum PedestrianSignal {
+    Walk,
+    DontWalk,
+    FlashingDontWalk,
+}
+
+/// Controller operating mode enumeration.
+///
+/// # Details
+/// Selects between normal color cycling and flashing operation.
+///
+/// # Variants
+/// * `Normal` - Cycle through the configured phases
+/// * `FlashingRed` - Fault detected, flash the red lamp only
+/// * `FlashingYellow` - Night mode, flash the yellow lamp only
+/// * `Preempted` - Emergency preemption, hold Green steadily
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum ControllerMode {
+    Normal,
+    FlashingRed,
+    FlashingYellow,
+    Preempted,
+}
+
+/// Mode flags carried in a packed status byte.
+///
+/// # Details
+/// Decoded by unpack_status(). At most one flag is set, since
+/// the controller modes are mutually exclusive.
+///
+/// # Fields
+/// * `fault` - Flashing red fault mode is active
+/// * `night` - Flashing yellow night mode is active
+/// * `preempt` - Emergency preemption is holding Green
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct StatusFlags {
+    pub fault: bool,
+    pub night: bool,
+    pub preempt: bool,
+}
+
+/// Status byte decoding error.
+///
+/// # Variants
+/// * `ReservedBits(u8)` - Byte has reserved bit 5 or 7 set
+/// * `ConflictingFlags(u8)` - Byte has more than one mode flag set
+/// * `InvalidState(u8)` - Byte has the RedYellow bit with a non-Red state code
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum StatusError {
+    ReservedBits(u8),
+    ConflictingFlags(u8),
+    InvalidState(u8),
+}
+
+impl core::fmt::Display for StatusError {
+    /// Formats the error as a human-readable message.
+    ///
+    /// # Arguments
+    /// * `f` - Formatter to write into
+    ///
+    /// # Returns
+    /// * `core::fmt::Result` - Result of the write
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            StatusError::ReservedBits(byte) => {
+                write!(f, "status byte {byte:#04x} has reserved bits set")
+            }
+            StatusError::ConflictingFlags(byte) => {
+                write!(f, "status byte {byte:#04x} has conflicting mode flags")
+            }
+            StatusError::InvalidState(byte) => {
+                write!(f, "status byte {byte:#04x} has an invalid state code")
+            }
+        }
+    }
+}
+
+/// Observed duration statistics for one phase.
+///
+/// # Details
+/// Collected by TrafficLightController::simulate_cycles().
+/// All fields are 0 when the phase was never observed.
+///
+/// # Fields
+/// * `min_ms` - Shortest observed duration in milliseconds
+/// * `max_ms` - Longest observed duration in milliseconds
+/// * `avg_ms` - Mean observed duration in milliseconds, truncated
+/// * `samples` - Number of completed phases observed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct PhaseStat {
+    pub min_ms: u64,
+    pub max_ms: u64,
+    pub avg_ms: u64,
+    pub samples: u32,
+}
+
+/// Per-phase statistics from a simulated run.
+///
+/// # Fields
+/// * `red` - Statistics for the Red phase
+/// * `yellow` - Statistics for the Yellow phase
+/// * `green` - Statistics for the Green phase
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct PhaseStats {
+    pub red: PhaseStat,
+    pub yellow: PhaseStat,
+    pub green: PhaseStat,
 }
 
 /// Traffic light controller with state tracking.
@@ -71,13 +456,79 @@ pub enum TrafficLightState {
 /// * `red_duration` - Duration for red light in milliseconds
 /// * `yellow_duration` - Duration for yellow light in milliseconds
 /// * `green_duration` - Duration for green light in milliseconds
+/// * `elapsed_in_phase` - Time spent in the current state in milliseconds
+/// * `dirty` - Set when timing changes after the phase timer was scheduled
+/// * `all_red_enabled` - Insert the AllRed clearance phase after Yellow
+/// * `all_red_duration` - Duration for the AllRed clearance in milliseconds
+/// * `red_yellow_enabled` - Insert the RedYellow get-ready phase before Green
+/// * `red_yellow_duration` - Duration for the RedYellow phase in milliseconds
+/// * `mode` - Current operating mode
+/// * `flash_lamp_on` - Lamp level while flashing in fault or night mode
+/// * `faulted_lamp` - Index of the lamp reported out, if any
+/// * `cycle_count` - Number of completed cycles, saturating
+/// * `night_flash_duration` - Yellow flash half-period in night mode in milliseconds
+/// * `min_green_ms` - Floor enforced by set_green_duration() in milliseconds
+/// * `flashes_remaining` - Red toggles left in a flash_red() self-test, 0 if none
+/// * `time_in_state` - Milliseconds ticked with the red, yellow and green lamp states, saturating
+/// * `dim_level` - Brightness of lit lamps, 0-255, used by lamp_duty()
+/// * `flashing_walk_ms` - Length of the flashing don't-walk countdown at the end of Red in milliseconds
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(dead_code)]
 pub struct TrafficLightController {
     current_state: TrafficLightState,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_duration"))]
     red_duration: u64,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_duration"))]
     yellow_duration: u64,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_duration"))]
     green_duration: u64,
+    elapsed_in_phase: u64,
+    dirty: bool,
+    all_red_enabled: bool,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_duration"))]
+    all_red_duration: u64,
+    red_yellow_enabled: bool,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_duration"))]
+    red_yellow_duration: u64,
+    mode: ControllerMode,
+    flash_lamp_on: bool,
+    faulted_lamp: Option<usize>,
+    cycle_count: u32,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_duration"))]
+    night_flash_duration: u64,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_duration"))]
+    min_green_ms: u64,
+    flashes_remaining: u32,
+    time_in_state: [u64; 3],
+    dim_level: u8,
+    flashing_walk_ms: u64,
+}
+
+/// Deserializes a duration and validates its range.
+///
+/// # Details
+/// Rejects values outside MIN_DURATION_MS..=MAX_DURATION_MS so a
+/// persisted timing profile cannot load an unsafe controller.
+///
+/// # Arguments
+/// * `deserializer` - Serde deserializer to read from
+///
+/// # Returns
+/// * `Result<u64, D::Error>` - Validated duration in milliseconds
+#[cfg(feature = "serde")]
+fn deserialize_duration<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let duration = <u64 as serde::Deserialize>::deserialize(deserializer)?;
+    validate_duration(duration).map_err(|_| {
+        serde::de::Error::invalid_value(
+            serde::de::Unexpected::Unsigned(duration),
+            &"a duration within MIN_DURATION_MS..=MAX_DURATION_MS",
+        )
+    })
 }
 
 impl Default for TrafficLightController {
@@ -99,595 +550,7951 @@ impl TrafficLightController {
     ///
     /// # Details
     /// Initializes controller starting at Red state.
+    /// Usable in const context, e.g. to initialize a static.
+    ///
+    /// # Returns
+    /// * `Self` - New TrafficLightController instance
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self::starting_at(TrafficLightState::Red)
+    }
+
+    /// Creates controller with default settings at a given state.
+    ///
+    /// # Details
+    /// Uses the default durations; useful for running two
+    /// intersections out of phase. Usable in const context.
+    ///
+    /// # Arguments
+    /// * `state` - State to start in
     ///
     /// # Returns
     /// * `Self` - New TrafficLightController instance
     #[allow(dead_code)]
-    pub fn new() -> Self {
+    pub const fn starting_at(state: TrafficLightState) -> Self {
         Self {
-            current_state: TrafficLightState::Red,
+            current_state: state,
             red_duration: RED_DURATION_MS,
             yellow_duration: YELLOW_DURATION_MS,
             green_duration: GREEN_DURATION_MS,
+            elapsed_in_phase: 0,
+            dirty: false,
+            all_red_enabled: false,
+            all_red_duration: ALL_RED_DURATION_MS,
+            red_yellow_enabled: false,
+            red_yellow_duration: RED_YELLOW_DURATION_MS,
+            mode: ControllerMode::Normal,
+            flash_lamp_on: true,
+            faulted_lamp: None,
+            cycle_count: 0,
+            night_flash_duration: NIGHT_FLASH_MS,
+            min_green_ms: MIN_DURATION_MS,
+            flashes_remaining: 0,
+            time_in_state: [0; 3],
+            dim_level: u8::MAX,
+            flashing_walk_ms: 0,
         }
     }
 
-    /// Advances to next state in sequence and returns new state.
+    /// Creates traffic light controller with custom durations.
     ///
     /// # Details
-    /// Transitions: Red -> Green -> Yellow -> Red.
-    /// Implements standard traffic light behavior.
+    /// Initializes controller starting at Red state. Applies the
+    /// same validate_durations() checks as the builder: each duration
+    /// within MIN_DURATION_MS..=MAX_DURATION_MS and yellow no longer
+    /// than red or green.
+    ///
+    /// # Arguments
+    /// * `red` - Red light duration in milliseconds
+    /// * `yellow` - Yellow light duration in milliseconds
+    /// * `green` - Green light duration in milliseconds
     ///
     /// # Returns
-    /// * `TrafficLightState` - New state after advancement
+    /// * `Result<Self, DurationError>` - New controller, or the first failed check
     #[allow(dead_code)]
-    pub fn advance(&mut self) -> TrafficLightState {
-        self.current_state = match self.current_state {
-            TrafficLightState::Red => TrafficLightState::Green,
-            TrafficLightState::Green => TrafficLightState::Yellow,
-            TrafficLightState::Yellow => TrafficLightState::Red,
-        };
-        self.current_state
+    pub fn with_durations(red: u64, yellow: u64, green: u64) -> Result<Self, DurationError> {
+        validate_durations(red, yellow, green)?;
+        Ok(Self {
+            red_duration: red,
+            yellow_duration: yellow,
+            green_duration: green,
+            ..Self::new()
+        })
     }
 
-    /// Returns current traffic light state.
+    /// Creates controller timed from a named preset.
     ///
     /// # Details
-    /// State of the traffic light.
+    /// Uses the durations of TimingPreset::plan() and starts at
+    /// Red with every other setting at its default.
+    ///
+    /// # Arguments
+    /// * `preset` - Intersection type to time for
     ///
     /// # Returns
-    /// * `TrafficLightState` - Current state
+    /// * `Self` - New TrafficLightController instance
     #[allow(dead_code)]
-    pub fn current_state(&self) -> TrafficLightState {
-        self.current_state
+    pub const fn from_preset(preset: TimingPreset) -> Self {
+        let plan = preset.plan();
+        Self {
+            red_duration: plan.red_duration,
+            yellow_duration: plan.yellow_duration,
+            green_duration: plan.green_duration,
+            ..Self::new()
+        }
     }
 
-    /// Returns duration for current state in milliseconds.
-    ///
-    /// # Details
-    /// Returns timing based on current state.
+    /// Starts building a controller.
     ///
     /// # Returns
-    /// * `u64` - Duration in milliseconds
+    /// * `TrafficLightControllerBuilder` - Builder with default settings
     #[allow(dead_code)]
-    pub fn current_duration(&self) -> u64 {
-        match self.current_state {
-            TrafficLightState::Red => self.red_duration,
-            TrafficLightState::Yellow => self.yellow_duration,
-            TrafficLightState::Green => self.green_duration,
-        }
+    pub fn builder() -> TrafficLightControllerBuilder {
+        TrafficLightControllerBuilder::new()
     }
 
-    /// Returns red light duration.
+    /// Enables or disables the all-red clearance phase.
     ///
     /// # Details
-    /// Duration for red state in milliseconds.
+    /// Builder-style flag; when enabled advance() inserts AllRed
+    /// between Yellow and Red for ALL_RED_DURATION_MS. Changes the
+    /// cycle length, so marks the controller for rescheduling.
+    ///
+    /// # Arguments
+    /// * `enabled` - true to insert the clearance phase
     ///
     /// # Returns
-    /// * `u64` - Red duration in milliseconds
+    /// * `Self` - Controller with the flag applied
     #[allow(dead_code)]
-    pub fn red_duration(&self) -> u64 {
-        self.red_duration
+    pub fn with_all_red(mut self, enabled: bool) -> Self {
+        self.all_red_enabled = enabled;
+        self.dirty = true;
+        self
     }
 
-    /// Returns yellow light duration.
+    /// Enables or disables the red+yellow get-ready phase.
     ///
     /// # Details
-    /// Duration for yellow state in milliseconds.
+    /// Builder-style flag; when enabled advance() inserts RedYellow
+    /// between Red and Green for RED_YELLOW_DURATION_MS. Changes the
+    /// cycle length, so marks the controller for rescheduling.
+    ///
+    /// # Arguments
+    /// * `enabled` - true to insert the get-ready phase
     ///
     /// # Returns
-    /// * `u64` - Yellow duration in milliseconds
+    /// * `Self` - Controller with the flag applied
     #[allow(dead_code)]
-    pub fn yellow_duration(&self) -> u64 {
-        self.yellow_duration
+    pub fn with_red_yellow(mut self, enabled: bool) -> Self {
+        self.red_yellow_enabled = enabled;
+        self.dirty = true;
+        self
     }
 
-    /// Returns green light duration.
+    /// Advances to next state in sequence and returns new state.
     ///
     /// # Details
-    /// Duration for green state in milliseconds.
+    /// Transitions: Red -> Green -> Yellow -> Red.
+    /// With the clearance phase enabled: Yellow -> AllRed -> Red.
+    /// With the get-ready phase enabled: Red -> RedYellow -> Green.
+    /// Implements standard traffic light behavior.
+    /// In fault or night mode the state is held and its lamp toggles.
+    /// While preempted the state is held at Green and nothing changes.
+    /// During a flash_red() self-test each toggle is counted and the
+    /// last one resumes normal cycling at Red.
+    /// Resets the elapsed time for the new phase and counts a
+    /// completed cycle on each return to Red.
     ///
     /// # Returns
-    /// * `u64` - Green duration in milliseconds
+    /// * `TrafficLightState` - New state after advancement
     #[allow(dead_code)]
-    pub fn green_duration(&self) -> u64 {
-        self.green_duration
+    pub fn advance(&mut self) -> TrafficLightState {
+        if self.is_preempted() {
+            return self.current_state;
+        }
+        if self.mode != ControllerMode::Normal {
+            self.toggle_flash();
+            if self.flashes_remaining > 0 {
+                self.flashes_remaining -= 1;
+                if self.flashes_remaining == 0 {
+                    self.reset();
+                }
+            }
+            return self.current_state;
+        }
+        self.current_state = match self.current_state {
+            TrafficLightState::Red if self.red_yellow_enabled => TrafficLightState::RedYellow,
+            TrafficLightState::Yellow if self.all_red_enabled => TrafficLightState::AllRed,
+            state => next_state(state),
+        };
+        if self.current_state == TrafficLightState::Red {
+            self.cycle_count = self.cycle_count.saturating_add(1);
+        }
+        self.elapsed_in_phase = 0;
+        self.current_state
     }
 
-    /// Returns true if red light should be on.
+    /// Advances n steps at once and returns the new state.
     ///
     /// # Details
-    /// Checks if current state is Red.
+    /// Equivalent to calling advance() n times, but runs in
+    /// constant time. In normal cycling every full period of the
+    /// cycle (3 phases, plus the optional clearance and get-ready
+    /// phases) returns to the same state and completes one cycle,
+    /// so only n modulo the period is stepped. Flashing modes only
+    /// need the parity of n; a flash_red() self-test that runs out
+    /// resumes cycling with the remaining steps. While preempted
+    /// nothing changes.
+    ///
+    /// # Arguments
+    /// * `n` - Number of advance() steps to apply
     ///
     /// # Returns
-    /// * `bool` - true if red, false otherwise
+    /// * `TrafficLightState` - New state after advancement
     #[allow(dead_code)]
-    pub fn is_red(&self) -> bool {
-        self.current_state == TrafficLightState::Red
+    pub fn advance_n(&mut self, n: u64) -> TrafficLightState {
+        let mut n = n;
+        if n == 0 || self.is_preempted() {
+            return self.current_state;
+        }
+        if self.mode != ControllerMode::Normal {
+            let flashes = self.flashes_remaining as u64;
+            if flashes == 0 || n < flashes {
+                self.flash_lamp_on ^= n % 2 == 1;
+                self.flashes_remaining -= n.min(flashes) as u32;
+                self.elapsed_in_phase = 0;
+                return self.current_state;
+            }
+            self.flashes_remaining = 1;
+            self.advance();
+            n -= flashes;
+        }
+        let period = 3 + self.all_red_enabled as u64 + self.red_yellow_enabled as u64;
+        let cycles = u32::try_from(n / period).unwrap_or(u32::MAX);
+        self.cycle_count = self.cycle_count.saturating_add(cycles);
+        for _ in 0..n % period {
+            self.advance();
+        }
+        self.elapsed_in_phase = 0;
+        self.current_state
     }
 
-    /// Returns true if yellow light should be on.
+    /// Returns the state advance() would produce without changing state.
     ///
     /// # Details
-    /// Checks if current state is Yellow.
+    /// Advances a copy of the controller, so the preview follows
+    /// every rule advance() applies, including the clearance phase
+    /// and flashing modes.
     ///
     /// # Returns
-    /// * `bool` - true if yellow, false otherwise
+    /// * `TrafficLightState` - Next state in sequence
     #[allow(dead_code)]
-    pub fn is_yellow(&self) -> bool {
-        self.current_state == TrafficLightState::Yellow
+    pub fn peek_next(&self) -> TrafficLightState {
+        let mut cursor = *self;
+        cursor.advance()
     }
 
-    /// Returns true if green light should be on.
+    /// Steps back to previous state in sequence and returns new state.
     ///
     /// # Details
-    /// Checks if current state is Green.
+    /// Transitions: Red -> Yellow -> Green -> Red.
+    /// With the clearance phase enabled: Red -> AllRed -> Yellow.
+    /// With the get-ready phase enabled: Green -> RedYellow -> Red.
+    /// Inverse of advance(), useful for stepping a rig backwards.
+    /// In fault or night mode the state is held and its lamp toggles.
+    /// While preempted the state is held at Green and nothing changes.
+    /// Resets the elapsed time for the new phase.
     ///
     /// # Returns
-    /// * `bool` - true if green, false otherwise
+    /// * `TrafficLightState` - New state after stepping back
     #[allow(dead_code)]
-    pub fn is_green(&self) -> bool {
-        self.current_state == TrafficLightState::Green
+    pub fn previous(&mut self) -> TrafficLightState {
+        if self.is_preempted() {
+            return self.current_state;
+        }
+        if self.mode != ControllerMode::Normal {
+            return self.toggle_flash();
+        }
+        self.current_state = match self.current_state {
+            TrafficLightState::Red if self.all_red_enabled => TrafficLightState::AllRed,
+            TrafficLightState::Red => TrafficLightState::Yellow,
+            TrafficLightState::AllRed => TrafficLightState::Yellow,
+            TrafficLightState::Yellow => TrafficLightState::Green,
+            TrafficLightState::Green if self.red_yellow_enabled => TrafficLightState::RedYellow,
+            TrafficLightState::Green => TrafficLightState::Red,
+            TrafficLightState::RedYellow => TrafficLightState::Red,
+        };
+        self.elapsed_in_phase = 0;
+        self.current_state
     }
-}
 
-/// Converts TrafficLightState to boolean for GPIO control.
-///
-/// # Details
-/// Maps specified state to true if current, false otherwise.
-///
-/// # Arguments
-/// * `current` - Current traffic light state
-/// * `target` - Target state to check
-///
+    /// Toggles the flashing lamp for one flash step.
+    ///
+    /// # Returns
+    /// * `TrafficLightState` - Held state (Red in fault, Yellow at night)
+    fn toggle_flash(&mut self) -> TrafficLightState {
+        self.flash_lamp_on = !self.flash_lamp_on;
+        self.elapsed_in_phase = 0;
+        self.current_state
+    }
+
+    /// Enters FlashingRed fault mode.
+    ///
+    /// # Details
+    /// Forces the state to Red with the red lamp lit. Each
+    /// advance() then toggles the lamp every FAULT_FLASH_MS.
+    /// A running flash_red() self-test becomes a latched fault.
+    /// Marks the controller for rescheduling.
+    #[allow(dead_code)]
+    pub fn enter_fault_mode(&mut self) {
+        self.mode = ControllerMode::FlashingRed;
+        self.flashes_remaining = 0;
+        self.current_state = TrafficLightState::Red;
+        self.elapsed_in_phase = 0;
+        self.flash_lamp_on = true;
+        self.dirty = true;
+    }
+
+    /// Leaves fault mode and resumes normal cycling.
+    ///
+    /// # Details
+    /// Restarts the cycle at Red with the lamp lit, forgets any
+    /// reported lamp fault and marks the controller for rescheduling.
+    #[allow(dead_code)]
+    pub fn clear_fault_mode(&mut self) {
+        self.reset();
+    }
+
+    /// Flashes red a fixed number of times, then resumes cycling.
+    ///
+    /// # Details
+    /// Self-test for the red lamp. Enters FlashingRed with the lamp
+    /// lit; each advance() toggles the lamp and counts down, and
+    /// the toggle that reaches zero returns the controller to
+    /// normal cycling at Red. is_fault() reads true while flashing.
+    /// Ignored for a zero count, while already in fault mode or
+    /// while preempted. Marks the controller for rescheduling.
+    ///
+    /// # Arguments
+    /// * `count` - Number of lamp toggles before resuming
+    #[allow(dead_code)]
+    pub fn flash_red(&mut self, count: u32) {
+        if count == 0 || self.is_fault() || self.is_preempted() {
+            return;
+        }
+        self.enter_fault_mode();
+        self.flashes_remaining = count;
+    }
+
+    /// Returns the lamp toggles left in a flash_red() self-test.
+    ///
+    /// # Returns
+    /// * `u32` - Remaining toggles, 0 if no self-test is running
+    #[allow(dead_code)]
+    pub fn flashes_remaining(&self) -> u32 {
+        self.flashes_remaining
+    }
+
+    /// Reports a lamp out and enters the failsafe mode.
+    ///
+    /// # Details
+    /// Called when lamp readback shows a lamp is not lit. Records
+    /// the lamp and enters FlashingRed fault mode, as a real
+    /// controller fails safe when it cannot show a phase.
+    ///
+    /// # Arguments
+    /// * `lamp_index` - Faulted lamp (0 red, 1 yellow, 2 green)
+    #[allow(dead_code)]
+    pub fn report_lamp_fault(&mut self, lamp_index: usize) {
+        self.faulted_lamp = Some(lamp_index);
+        self.enter_fault_mode();
+    }
+
+    /// Enables or disables night-mode flashing yellow.
+    ///
+    /// # Details
+    /// Enabling holds the state at Yellow with the lamp lit; each
+    /// advance() then toggles between yellow on and all off every
+    /// night_flash_duration. Disabling restarts normal cycling at
+    /// Red. Ignored while in fault mode or preempted, which both
+    /// take priority. Marks the controller for rescheduling.
+    ///
+    /// # Arguments
+    /// * `enabled` - true to flash yellow, false to cycle normally
+    #[allow(dead_code)]
+    pub fn set_night_mode(&mut self, enabled: bool) {
+        if self.is_fault() || self.is_preempted() || enabled == self.is_night_mode() {
+            return;
+        }
+        if enabled {
+            self.mode = ControllerMode::FlashingYellow;
+            self.current_state = TrafficLightState::Yellow;
+            self.elapsed_in_phase = 0;
+        } else {
+            self.mode = ControllerMode::Normal;
+            self.reset();
+        }
+        self.flash_lamp_on = true;
+        self.dirty = true;
+    }
+
+    /// Returns true if night-mode flashing yellow is active.
+    ///
+    /// # Returns
+    /// * `bool` - true while flashing yellow
+    #[allow(dead_code)]
+    pub fn is_night_mode(&self) -> bool {
+        self.mode == ControllerMode::FlashingYellow
+    }
+
+    /// Forces the light to Green for emergency-vehicle preemption.
+    ///
+    /// # Details
+    /// Switches to steady Green immediately and holds it: advance(),
+    /// previous() and tick() leave the state unchanged until
+    /// clear_preempt(). Overrides night mode but is ignored while in
+    /// fault mode, since a faulted controller must not show Green.
+    /// Marks the controller for rescheduling.
+    #[allow(dead_code)]
+    pub fn preempt_green(&mut self) {
+        if self.is_fault() {
+            return;
+        }
+        self.mode = ControllerMode::Preempted;
+        self.current_state = TrafficLightState::Green;
+        self.elapsed_in_phase = 0;
+        self.flash_lamp_on = true;
+        self.dirty = true;
+    }
+
+    /// Ends emergency-vehicle preemption and resumes normal cycling.
+    ///
+    /// # Details
+    /// Cycling continues from the held Green with a fresh phase, so
+    /// traffic gets its full green before the normal Yellow. Does
+    /// nothing if the controller is not preempted. Marks the
+    /// controller for rescheduling.
+    #[allow(dead_code)]
+    pub fn clear_preempt(&mut self) {
+        if !self.is_preempted() {
+            return;
+        }
+        self.mode = ControllerMode::Normal;
+        self.elapsed_in_phase = 0;
+        self.dirty = true;
+    }
+
+    /// Returns true while emergency-vehicle preemption holds Green.
+    ///
+    /// # Returns
+    /// * `bool` - true while preempted
+    #[allow(dead_code)]
+    pub fn is_preempted(&self) -> bool {
+        self.mode == ControllerMode::Preempted
+    }
+
+    /// Returns the night-mode flash half-period.
+    ///
+    /// # Returns
+    /// * `u64` - Duration in milliseconds
+    #[allow(dead_code)]
+    pub fn night_flash_duration(&self) -> u64 {
+        self.night_flash_duration
+    }
+
+    /// Returns the lamp reported out, if any.
+    ///
+    /// # Returns
+    /// * `Option<usize>` - Faulted lamp index, None if no lamp fault
+    #[allow(dead_code)]
+    pub fn faulted_lamp(&self) -> Option<usize> {
+        self.faulted_lamp
+    }
+
+    /// Returns true if the controller is in fault mode.
+    ///
+    /// # Returns
+    /// * `bool` - true while flashing red
+    #[allow(dead_code)]
+    pub fn is_fault(&self) -> bool {
+        self.mode == ControllerMode::FlashingRed
+    }
+
+    /// Returns the current operating mode.
+    ///
+    /// # Returns
+    /// * `ControllerMode` - Normal, FlashingRed, FlashingYellow or Preempted
+    #[allow(dead_code)]
+    pub fn mode(&self) -> ControllerMode {
+        self.mode
+    }
+
+    /// Returns the controller to its initial Red state.
+    ///
+    /// # Details
+    /// Sets the state back to Red and clears the elapsed phase
+    /// time. Leaves night mode, fault mode, a running flash_red()
+    /// self-test and preemption, so normal cycling resumes with the
+    /// lamp lit and no lamp fault reported. Configured durations and
+    /// options are left untouched. Marks the controller for
+    /// rescheduling.
+    #[allow(dead_code)]
+    pub fn reset(&mut self) {
+        self.mode = ControllerMode::Normal;
+        self.current_state = TrafficLightState::Red;
+        self.elapsed_in_phase = 0;
+        self.flash_lamp_on = true;
+        self.flashes_remaining = 0;
+        self.faulted_lamp = None;
+        self.dirty = true;
+    }
+
+    /// Advances the controller by a simulated time step.
+    ///
+    /// # Details
+    /// Accumulates elapsed time and advances whenever the current
+    /// phase duration is reached, carrying any remainder forward.
+    /// A large step may advance through several phases; whole
+    /// cycles are skipped arithmetically, so even u64::MAX returns
+    /// promptly. While preempted no transition occurs and the
+    /// elapsed phase time stops at the green duration, so a long
+    /// hold cannot grow it without bound. The time spent in each
+    /// phase is added to time_in_state().
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time elapsed since the previous tick in milliseconds
+    ///
+    /// # Returns
+    /// * `Option<TrafficLightState>` - New state if a transition occurred, None otherwise
+    #[allow(dead_code)]
+    pub fn tick(&mut self, elapsed_ms: u64) -> Option<TrafficLightState> {
+        let mut remaining = self.elapsed_in_phase.saturating_add(elapsed_ms);
+        if self.is_preempted() {
+            self.record_time(elapsed_ms);
+            self.elapsed_in_phase = remaining.min(self.green_duration);
+            return None;
+        }
+        let mut counted = self.elapsed_in_phase;
+        let mut transitioned = None;
+        loop {
+            let skipped = self.skip_whole_cycles(remaining - counted);
+            if skipped > 0 {
+                remaining -= skipped;
+                transitioned = Some(self.current_state);
+            }
+            if remaining < self.current_duration() {
+                break;
+            }
+            let duration = self.current_duration();
+            self.record_time(duration.saturating_sub(counted));
+            counted = 0;
+            remaining -= duration;
+            transitioned = Some(self.advance());
+        }
+        self.record_time(remaining.saturating_sub(counted));
+        self.elapsed_in_phase = remaining;
+        transitioned
+    }
+
+    /// Applies every whole cycle that fits in a time step.
+    ///
+    /// # Details
+    /// A full cycle from any point returns to that same point, so
+    /// whole cycles need no stepping. Normal cycling adds each
+    /// phase's share to time_in_state() and counts one completed
+    /// cycle per repeat. Flashing skips on/off pairs; a flash_red()
+    /// self-test keeps its last toggles for tick() to step, so it
+    /// resumes cycling at the right moment.
+    ///
+    /// # Arguments
+    /// * `new_ms` - Time not yet accounted for in milliseconds
+    ///
+    /// # Returns
+    /// * `u64` - Time consumed by the skipped cycles in milliseconds
+    fn skip_whole_cycles(&mut self, new_ms: u64) -> u64 {
+        if self.mode == ControllerMode::Normal {
+            let period = self.total_cycle_duration();
+            let cycles = new_ms / period;
+            if cycles == 0 {
+                return 0;
+            }
+            let mut shares = [0u64; 3];
+            for (state, duration) in self.state_sequence() {
+                let share = &mut shares[state_to_pin_index(state)];
+                *share = share.saturating_add(duration.saturating_mul(cycles));
+            }
+            for (slot, share) in self.time_in_state.iter_mut().zip(shares) {
+                *slot = slot.saturating_add(share);
+            }
+            let completed = u32::try_from(cycles).unwrap_or(u32::MAX);
+            self.cycle_count = self.cycle_count.saturating_add(completed);
+            cycles * period
+        } else {
+            let period = self.current_duration().saturating_mul(2);
+            let mut pairs = new_ms / period;
+            if self.flashes_remaining > 0 {
+                pairs = pairs.min(((self.flashes_remaining - 1) / 2) as u64);
+                self.flashes_remaining -= pairs as u32 * 2;
+            }
+            self.record_time(pairs * period);
+            pairs * period
+        }
+    }
+
+    /// Adds ticked time to the current state's accumulator.
+    ///
+    /// # Arguments
+    /// * `ms` - Time spent in the current state in milliseconds
+    fn record_time(&mut self, ms: u64) {
+        let slot = &mut self.time_in_state[state_to_pin_index(self.current_state)];
+        *slot = slot.saturating_add(ms);
+    }
+
+    /// Returns cumulative ticked time spent in a state.
+    ///
+    /// # Details
+    /// Accumulated by tick() with saturating addition, per lamp
+    /// color: AllRed and RedYellow count towards Red, matching
+    /// state_to_pin_index(). Flashing modes count towards their
+    /// held state. Time stepped with advance() alone is not
+    /// counted.
+    ///
+    /// # Arguments
+    /// * `state` - State to look up
+    ///
+    /// # Returns
+    /// * `u64` - Total time in milliseconds
+    #[allow(dead_code)]
+    pub fn time_in_state(&self, state: TrafficLightState) -> u64 {
+        self.time_in_state[state_to_pin_index(state)]
+    }
+
+    /// Advances by observed wall time and reports clock drift.
+    ///
+    /// # Details
+    /// Ticks by the observed delta, since that is the time that
+    /// actually passed, and compares it with the intended delta.
+    /// A positive drift means the timer fired late (slow clock),
+    /// a negative drift means it fired early (fast clock).
+    ///
+    /// # Arguments
+    /// * `intended_ms` - Delta the caller asked the timer for in milliseconds
+    /// * `observed_ms` - Delta measured against wall time in milliseconds
+    ///
+    /// # Returns
+    /// * `i64` - Drift (observed - intended) in milliseconds, saturated to i64
+    #[allow(dead_code)]
+    pub fn tick_with_reference(&mut self, intended_ms: u64, observed_ms: u64) -> i64 {
+        self.tick(observed_ms);
+        let drift = observed_ms as i128 - intended_ms as i128;
+        drift.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    /// Returns number of completed cycles.
+    ///
+    /// # Details
+    /// Incremented by advance() on each return to Red and
+    /// saturates at u32::MAX. Fault flashing and reset() do not
+    /// change it.
+    ///
+    /// # Returns
+    /// * `u32` - Completed Red -> Green -> Yellow -> Red loops
+    #[allow(dead_code)]
+    pub fn cycle_count(&self) -> u32 {
+        self.cycle_count
+    }
+
+    /// Returns time spent in the current phase.
+    ///
+    /// # Details
+    /// Accumulated by tick() and reset on every transition.
+    ///
+    /// # Returns
+    /// * `u64` - Elapsed time in milliseconds
+    #[allow(dead_code)]
+    pub fn elapsed_in_phase(&self) -> u64 {
+        self.elapsed_in_phase
+    }
+
+    /// Returns time until the currently lit lamp turns off.
+    ///
+    /// # Details
+    /// The current lamp extinguishes at the next transition, so
+    /// this is the time remaining in the current phase. Lets a
+    /// lamp driver prepare the next lamp ahead of the switch.
+    ///
+    /// # Returns
+    /// * `u64` - Remaining phase time in milliseconds
+    #[allow(dead_code)]
+    pub fn current_lamp_off_in(&self) -> u64 {
+        self.remaining_ms(self.elapsed_in_phase)
+    }
+
+    /// Returns time left in the current phase.
+    ///
+    /// # Details
+    /// Saturates at 0 if the caller's elapsed time overshoots the
+    /// phase duration.
+    ///
+    /// # Arguments
+    /// * `elapsed_in_phase` - Time spent in the current phase in milliseconds
+    ///
+    /// # Returns
+    /// * `u64` - Remaining time in milliseconds
+    #[allow(dead_code)]
+    pub fn remaining_ms(&self, elapsed_in_phase: u64) -> u64 {
+        self.current_duration().saturating_sub(elapsed_in_phase)
+    }
+
+    /// Returns time until the next occurrence of a state.
+    ///
+    /// # Details
+    /// Sums the time left in the current phase and the full
+    /// durations of the phases in between, following
+    /// state_sequence() order. Returns u64::MAX if the target is
+    /// never reached, e.g. AllRed with the clearance phase disabled
+    /// or any other state while flashing or preempted.
+    ///
+    /// # Arguments
+    /// * `target` - State to count down to
+    /// * `elapsed_in_phase` - Time spent in the current phase in milliseconds
+    ///
+    /// # Returns
+    /// * `u64` - Milliseconds until target, 0 if already in target
+    #[allow(dead_code)]
+    pub fn time_until(&self, target: TrafficLightState, elapsed_in_phase: u64) -> u64 {
+        if self.current_state == target {
+            return 0;
+        }
+        let mut total = self.remaining_ms(elapsed_in_phase);
+        for (state, duration) in self.state_sequence().skip(1) {
+            if state == target {
+                return total;
+            }
+            total = total.saturating_add(duration);
+        }
+        u64::MAX
+    }
+
+    /// Returns true if the current phase has run its full duration.
+    ///
+    /// # Arguments
+    /// * `elapsed_in_phase` - Time spent in the current phase in milliseconds
+    ///
+    /// # Returns
+    /// * `bool` - true once elapsed reaches the phase duration
+    #[allow(dead_code)]
+    pub fn is_phase_complete(&self, elapsed_in_phase: u64) -> bool {
+        elapsed_in_phase >= self.current_duration()
+    }
+
+    /// Returns how much of the current phase has completed.
+    ///
+    /// # Details
+    /// Computed as elapsed * 100 / current_duration() and capped at
+    /// 100. A zero-length phase counts as complete.
+    ///
+    /// # Arguments
+    /// * `elapsed_in_phase` - Time spent in the current phase in milliseconds
+    ///
+    /// # Returns
+    /// * `u8` - Progress percentage (0-100)
+    #[allow(dead_code)]
+    pub fn phase_progress(&self, elapsed_in_phase: u64) -> u8 {
+        let duration = self.current_duration();
+        if duration == 0 {
+            return 100;
+        }
+        (elapsed_in_phase.saturating_mul(100) / duration).min(100) as u8
+    }
+
+    /// Returns time elapsed since the cycle last started at Red.
+    ///
+    /// # Details
+    /// Sums the durations of phases completed since Red began
+    /// plus the time spent in the current phase, saturating at
+    /// u64::MAX.
+    ///
+    /// # Returns
+    /// * `u64` - Within-cycle elapsed time in milliseconds
+    #[allow(dead_code)]
+    pub fn ms_since_cycle_start(&self) -> u64 {
+        self.phase_start_offset(self.current_state)
+            .saturating_add(self.elapsed_in_phase)
+    }
+
+    /// Returns the state active at an offset into the cycle.
+    ///
+    /// # Details
+    /// The offset is measured from the start of Red and wrapped
+    /// by the cycle length, so any offset is accepted.
+    ///
+    /// # Arguments
+    /// * `offset_ms` - Offset from cycle start in milliseconds
+    ///
+    /// # Returns
+    /// * `TrafficLightState` - State active at that offset
+    #[allow(dead_code)]
+    pub fn phase_at_offset(&self, offset_ms: u64) -> TrafficLightState {
+        let offset = offset_ms % self.total_cycle_duration();
+        if offset < self.phase_start_offset(TrafficLightState::RedYellow) {
+            TrafficLightState::Red
+        } else if offset < self.phase_start_offset(TrafficLightState::Green) {
+            TrafficLightState::RedYellow
+        } else if offset < self.phase_start_offset(TrafficLightState::Yellow) {
+            TrafficLightState::Green
+        } else if offset < self.phase_start_offset(TrafficLightState::AllRed) {
+            TrafficLightState::Yellow
+        } else {
+            TrafficLightState::AllRed
+        }
+    }
+
+    /// Returns phase start times of one cycle as seconds-of-day.
+    ///
+    /// # Details
+    /// Adds each phase's start offset (truncated to whole seconds)
+    /// to the cycle start and wraps at midnight (86400 s). The
+    /// AllRed clearance and RedYellow get-ready starts are not
+    /// included.
+    ///
+    /// # Arguments
+    /// * `cycle_start_sod` - Cycle start in seconds since midnight
+    ///
+    /// # Returns
+    /// * `[u32; 3]` - Start times of [Red, Green, Yellow] in seconds since midnight
+    #[allow(dead_code)]
+    pub fn transition_times_of_day(&self, cycle_start_sod: u32) -> [u32; 3] {
+        [
+            TrafficLightState::Red,
+            TrafficLightState::Green,
+            TrafficLightState::Yellow,
+        ]
+        .map(|state| {
+            let start_s = cycle_start_sod as u64 + self.phase_start_offset(state) / 1000;
+            (start_s % SECONDS_PER_DAY) as u32
+        })
+    }
+
+    /// Returns the state at a given cycle count and offset.
+    ///
+    /// # Details
+    /// The controller is periodic with one full cycle as its period,
+    /// so the cycle count never affects the result and only the
+    /// offset within the cycle matters.
+    ///
+    /// # Arguments
+    /// * `_cycles` - Number of completed cycles (ignored by periodicity)
+    /// * `offset_ms` - Offset into the cycle in milliseconds
+    ///
+    /// # Returns
+    /// * `TrafficLightState` - State at that point in the history
+    #[allow(dead_code)]
+    pub fn state_at(&self, _cycles: u64, offset_ms: u64) -> TrafficLightState {
+        self.phase_at_offset(offset_ms)
+    }
+
+    /// Returns each state of one cycle paired with its duration.
+    ///
+    /// # Details
+    /// Starts at the current state and follows advance() order,
+    /// yielding every phase exactly once (three, plus one each for
+    /// the clearance and get-ready phases when enabled). The
+    /// controller is not modified.
+    ///
+    /// # Returns
+    /// * `impl Iterator<Item = (TrafficLightState, u64)>` - States with durations in milliseconds
+    #[allow(dead_code)]
+    pub fn state_sequence(&self) -> impl Iterator<Item = (TrafficLightState, u64)> {
+        let phases = 3 + self.all_red_enabled as u8 + self.red_yellow_enabled as u8;
+        let mut cursor = *self;
+        (0..phases).map(move |_| {
+            let state = cursor.current_state;
+            cursor.advance();
+            (state, cursor.duration_of(state))
+        })
+    }
+
+    /// Builds a timeline of phase start times.
+    ///
+    /// # Details
+    /// Lists every phase over the requested number of cycles,
+    /// starting with the current state at t=0 and following
+    /// advance() order. Each start time is the previous one plus
+    /// that phase's duration; time already elapsed in the current
+    /// phase is ignored. Entries beyond SCHEDULE_CAPACITY are
+    /// dropped. The controller is not modified.
+    ///
+    /// # Arguments
+    /// * `cycles` - Number of full cycles to list
+    ///
+    /// # Returns
+    /// * `heapless::Vec<(u64, TrafficLightState), SCHEDULE_CAPACITY>` - (start_time_ms, state) entries
+    #[allow(dead_code)]
+    pub fn schedule(
+        &self,
+        cycles: u32,
+    ) -> heapless::Vec<(u64, TrafficLightState), SCHEDULE_CAPACITY> {
+        let mut table = heapless::Vec::new();
+        let mut start: u64 = 0;
+        for _ in 0..cycles {
+            for (state, duration) in self.state_sequence() {
+                if table.push((start, state)).is_err() {
+                    return table;
+                }
+                start = start.saturating_add(duration);
+            }
+        }
+        table
+    }
+
+    /// Returns an endless iterator over the state sequence.
+    ///
+    /// # Details
+    /// Starts at the current state and follows advance() order
+    /// forever, so callers cap it with take() or a loop break.
+    /// Iterates a copy; the controller is not modified.
+    ///
+    /// # Returns
+    /// * `StateCycle` - Iterator yielding states indefinitely
+    #[allow(dead_code)]
+    pub fn cycle(&self) -> StateCycle {
+        StateCycle { cursor: *self }
+    }
+
+    /// Returns how many phases follow the current one in this cycle.
+    ///
+    /// # Details
+    /// Counts the phases still to be shown after the current one
+    /// before the cycle completes by returning to Red. This is one
+    /// less than the number of advance() calls needed to reach Red:
+    /// 2 from Red, 1 from Green, 0 from Yellow. With the clearance
+    /// phase enabled Yellow gives 1 and AllRed gives 0.
+    /// Flashing modes do not cycle, so they give 0.
+    ///
+    /// # Returns
+    /// * `u8` - Remaining phases in the current cycle
+    #[allow(dead_code)]
+    pub fn phases_remaining_in_cycle(&self) -> u8 {
+        if self.mode != ControllerMode::Normal {
+            return 0;
+        }
+        let mut cursor = *self;
+        let mut remaining = 0;
+        while cursor.advance() != TrafficLightState::Red {
+            remaining += 1;
+        }
+        remaining
+    }
+
+    /// Returns current traffic light state.
+    ///
+    /// # Details
+    /// State of the traffic light.
+    ///
+    /// # Returns
+    /// * `TrafficLightState` - Current state
+    #[allow(dead_code)]
+    pub fn current_state(&self) -> TrafficLightState {
+        self.current_state
+    }
+
+    /// Returns duration for current state in milliseconds.
+    ///
+    /// # Details
+    /// Returns timing based on current state, or the flash
+    /// half-period while in fault or night mode. Preemption holds
+    /// Green, so it reports the green duration.
+    ///
+    /// # Returns
+    /// * `u64` - Duration in milliseconds
+    #[allow(dead_code)]
+    pub fn current_duration(&self) -> u64 {
+        match self.mode {
+            ControllerMode::Normal => self.duration_of(self.current_state),
+            ControllerMode::FlashingRed => FAULT_FLASH_MS,
+            ControllerMode::FlashingYellow => self.night_flash_duration,
+            ControllerMode::Preempted => self.green_duration,
+        }
+    }
+
+    /// Returns duration configured for a given state.
+    ///
+    /// # Arguments
+    /// * `state` - State to look up
+    ///
+    /// # Returns
+    /// * `u64` - Duration in milliseconds
+    fn duration_of(&self, state: TrafficLightState) -> u64 {
+        match state {
+            TrafficLightState::Red => self.red_duration,
+            TrafficLightState::Yellow => self.yellow_duration,
+            TrafficLightState::Green => self.green_duration,
+            TrafficLightState::AllRed => self.all_red_duration,
+            TrafficLightState::RedYellow => self.red_yellow_duration,
+        }
+    }
+
+    /// Returns duration of the clearance phase within the cycle.
+    ///
+    /// # Returns
+    /// * `u64` - All-red duration if enabled, 0 otherwise
+    fn clearance_duration(&self) -> u64 {
+        if self.all_red_enabled {
+            self.all_red_duration
+        } else {
+            0
+        }
+    }
+
+    /// Returns duration of the get-ready phase within the cycle.
+    ///
+    /// # Returns
+    /// * `u64` - Red+yellow duration if enabled, 0 otherwise
+    fn get_ready_duration(&self) -> u64 {
+        if self.red_yellow_enabled {
+            self.red_yellow_duration
+        } else {
+            0
+        }
+    }
+
+    /// Returns length of one full cycle.
+    ///
+    /// # Details
+    /// Sums red, yellow and green, plus the all-red clearance and
+    /// red+yellow get-ready phases when enabled. Uses checked_add
+    /// and saturates to u64::MAX rather than wrapping on overflow.
+    ///
+    /// # Returns
+    /// * `u64` - Full cycle period in milliseconds
+    #[allow(dead_code)]
+    pub fn total_cycle_duration(&self) -> u64 {
+        accumulate_durations(&[
+            self.red_duration,
+            self.yellow_duration,
+            self.green_duration,
+            self.clearance_duration(),
+            self.get_ready_duration(),
+        ])
+        .unwrap_or(u64::MAX)
+    }
+
+    /// Returns the longest time a vehicle can be held at red.
+    ///
+    /// # Details
+    /// A vehicle arriving as the red lamp comes on waits through
+    /// the all-red clearance, Red and the red+yellow get-ready
+    /// phase, each counted only when enabled. Sums with saturating
+    /// addition.
+    ///
+    /// # Returns
+    /// * `u64` - Worst-case red wait in milliseconds
+    #[allow(dead_code)]
+    pub fn max_red_wait(&self) -> u64 {
+        self.red_duration
+            .saturating_add(self.clearance_duration())
+            .saturating_add(self.get_ready_duration())
+    }
+
+    /// Compares two controllers by total cycle length.
+    ///
+    /// # Details
+    /// Orders by total_cycle_duration() only, ignoring state and
+    /// per-phase splits, so two different profiles with the same
+    /// cycle length compare Equal. Useful for picking the faster
+    /// of two timing profiles.
+    ///
+    /// # Arguments
+    /// * `other` - Controller to compare against
+    ///
+    /// # Returns
+    /// * `core::cmp::Ordering` - Less if this cycle is shorter than other's
+    #[allow(dead_code)]
+    pub fn cmp_cycle(&self, other: &Self) -> core::cmp::Ordering {
+        self.total_cycle_duration()
+            .cmp(&other.total_cycle_duration())
+    }
+
+    /// Returns offset of a state's start within the cycle.
+    ///
+    /// # Details
+    /// The cycle starts at Red, so Red is at 0, RedYellow follows
+    /// Red, Green follows RedYellow, Yellow follows Green, and AllRed
+    /// follows Yellow. Disabled optional phases take no time.
+    ///
+    /// # Arguments
+    /// * `state` - State to locate
+    ///
+    /// # Returns
+    /// * `u64` - Offset from cycle start in milliseconds
+    fn phase_start_offset(&self, state: TrafficLightState) -> u64 {
+        let green_start = self.red_duration + self.get_ready_duration();
+        match state {
+            TrafficLightState::Red => 0,
+            TrafficLightState::RedYellow => self.red_duration,
+            TrafficLightState::Green => green_start,
+            TrafficLightState::Yellow => green_start + self.green_duration,
+            TrafficLightState::AllRed => green_start + self.green_duration + self.yellow_duration,
+        }
+    }
+
+    /// Returns red light duration.
+    ///
+    /// # Details
+    /// Duration for red state in milliseconds.
+    ///
+    /// # Returns
+    /// * `u64` - Red duration in milliseconds
+    #[allow(dead_code)]
+    pub fn red_duration(&self) -> u64 {
+        self.red_duration
+    }
+
+    /// Returns yellow light duration.
+    ///
+    /// # Details
+    /// Duration for yellow state in milliseconds.
+    ///
+    /// # Returns
+    /// * `u64` - Yellow duration in milliseconds
+    #[allow(dead_code)]
+    pub fn yellow_duration(&self) -> u64 {
+        self.yellow_duration
+    }
+
+    /// Returns green light duration.
+    ///
+    /// # Details
+    /// Duration for green state in milliseconds.
+    ///
+    /// # Returns
+    /// * `u64` - Green duration in milliseconds
+    #[allow(dead_code)]
+    pub fn green_duration(&self) -> u64 {
+        self.green_duration
+    }
+
+    /// Sets red light duration.
+    ///
+    /// # Details
+    /// Rejects values outside MIN_DURATION_MS..=MAX_DURATION_MS and
+    /// marks the controller for rescheduling on success.
+    ///
+    /// # Arguments
+    /// * `duration` - New red duration in milliseconds, as u64 or Millis
+    ///
+    /// # Returns
+    /// * `Result<(), DurationError>` - Ok, or the rejected duration
+    #[allow(dead_code)]
+    pub fn set_red_duration(&mut self, duration: impl Into<Millis>) -> Result<(), DurationError> {
+        self.red_duration = duration.into().validate()?.into();
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Sets yellow light duration.
+    ///
+    /// # Details
+    /// Rejects values outside MIN_DURATION_MS..=MAX_DURATION_MS and
+    /// marks the controller for rescheduling on success.
+    ///
+    /// # Arguments
+    /// * `duration` - New yellow duration in milliseconds, as u64 or Millis
+    ///
+    /// # Returns
+    /// * `Result<(), DurationError>` - Ok, or the rejected duration
+    #[allow(dead_code)]
+    pub fn set_yellow_duration(
+        &mut self,
+        duration: impl Into<Millis>,
+    ) -> Result<(), DurationError> {
+        self.yellow_duration = duration.into().validate()?.into();
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Sets green light duration.
+    ///
+    /// # Details
+    /// Rejects values outside MIN_DURATION_MS..=MAX_DURATION_MS or
+    /// below the minimum green set by set_min_green(), and marks the
+    /// controller for rescheduling on success.
+    ///
+    /// # Arguments
+    /// * `duration` - New green duration in milliseconds, as u64 or Millis
+    ///
+    /// # Returns
+    /// * `Result<(), DurationError>` - Ok, or the rejected duration
+    #[allow(dead_code)]
+    pub fn set_green_duration(&mut self, duration: impl Into<Millis>) -> Result<(), DurationError> {
+        let duration = duration.into();
+        if duration < Millis(self.min_green_ms) {
+            return Err(DurationError::TooShort(duration.into()));
+        }
+        self.green_duration = duration.validate()?.into();
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Scales every cycle duration by a fraction.
+    ///
+    /// # Details
+    /// Multiplies red, yellow, green, the all-red clearance and the
+    /// red+yellow get-ready durations by numerator / denominator,
+    /// truncating, e.g. 1/10 to run the cycle ten times faster.
+    /// Flash periods are left unchanged. Uses checked arithmetic:
+    /// an overflowing product or a zero denominator is rejected as
+    /// TooLong(u64::MAX). Every scaled duration is validated before
+    /// anything changes, so on failure the existing durations are
+    /// left untouched. Marks the controller for rescheduling on
+    /// success.
+    ///
+    /// # Arguments
+    /// * `numerator` - Scale factor numerator
+    /// * `denominator` - Scale factor denominator
+    ///
+    /// # Returns
+    /// * `Result<(), DurationError>` - Ok, or the first scaled duration rejected
+    #[allow(dead_code)]
+    pub fn scale_durations(
+        &mut self,
+        numerator: u32,
+        denominator: u32,
+    ) -> Result<(), DurationError> {
+        let scale = |duration: u64| {
+            duration
+                .checked_mul(numerator as u64)
+                .and_then(|product| product.checked_div(denominator as u64))
+                .ok_or(DurationError::TooLong(u64::MAX))
+        };
+        let red = scale(self.red_duration)?;
+        let yellow = scale(self.yellow_duration)?;
+        let green = scale(self.green_duration)?;
+        let all_red = scale(self.all_red_duration)?;
+        let red_yellow = scale(self.red_yellow_duration)?;
+        validate_durations(red, yellow, green)?;
+        validate_duration(all_red)?;
+        validate_duration(red_yellow)?;
+        self.red_duration = red;
+        self.yellow_duration = yellow;
+        self.green_duration = green;
+        self.all_red_duration = all_red;
+        self.red_yellow_duration = red_yellow;
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Builds a controller timed at the midpoint of two profiles.
+    ///
+    /// # Details
+    /// Averages the red, yellow, green, all-red and red-yellow
+    /// durations, rounding down. The sums use checked addition and
+    /// report overflow as DurationError::TooLong(u64::MAX). The
+    /// result is validated like scale_durations() and starts at Red
+    /// with default runtime state, keeping this controller's
+    /// clearance and get-ready options.
+    ///
+    /// # Arguments
+    /// * `other` - Profile to average with
+    ///
+    /// # Returns
+    /// * `Result<Self, DurationError>` - Averaged controller, or the first invalid duration
+    #[allow(dead_code)]
+    pub fn average_with(&self, other: &Self) -> Result<Self, DurationError> {
+        let mean = |a: u64, b: u64| {
+            a.checked_add(b)
+                .map(|sum| sum / 2)
+                .ok_or(DurationError::TooLong(u64::MAX))
+        };
+        let red = mean(self.red_duration, other.red_duration)?;
+        let yellow = mean(self.yellow_duration, other.yellow_duration)?;
+        let green = mean(self.green_duration, other.green_duration)?;
+        let all_red = mean(self.all_red_duration, other.all_red_duration)?;
+        let red_yellow = mean(self.red_yellow_duration, other.red_yellow_duration)?;
+        validate_durations(red, yellow, green)?;
+        validate_duration(all_red)?;
+        validate_duration(red_yellow)?;
+        Ok(Self {
+            red_duration: red,
+            yellow_duration: yellow,
+            green_duration: green,
+            all_red_enabled: self.all_red_enabled,
+            all_red_duration: all_red,
+            red_yellow_enabled: self.red_yellow_enabled,
+            red_yellow_duration: red_yellow,
+            ..Self::new()
+        })
+    }
+
+    /// Sets the minimum green enforced on live timing changes.
+    ///
+    /// # Details
+    /// set_green_duration() rejects values below this floor with
+    /// DurationError::TooShort, independent of MIN_DURATION_MS.
+    /// The floor is clamped into MIN_DURATION_MS..=MAX_DURATION_MS.
+    /// The current green duration is left unchanged.
+    ///
+    /// # Arguments
+    /// * `ms` - Minimum green duration in milliseconds
+    #[allow(dead_code)]
+    pub fn set_min_green(&mut self, ms: u64) {
+        self.min_green_ms = ms.clamp(MIN_DURATION_MS, MAX_DURATION_MS);
+    }
+
+    /// Returns the minimum green enforced by set_green_duration().
+    ///
+    /// # Returns
+    /// * `u64` - Minimum green duration in milliseconds
+    #[allow(dead_code)]
+    pub fn min_green_ms(&self) -> u64 {
+        self.min_green_ms
+    }
+
+    /// Sets the brightness of lit lamps.
+    ///
+    /// # Details
+    /// Lets night operation keep lamps lit but dimmed to save
+    /// power. 255 is full brightness and 0 keeps every lamp dark.
+    /// Affects lamp_duty() only; timing is unchanged.
+    ///
+    /// # Arguments
+    /// * `level` - Brightness from 0 to 255
+    #[allow(dead_code)]
+    pub fn set_dim_level(&mut self, level: u8) {
+        self.dim_level = level;
+    }
+
+    /// Returns the brightness of lit lamps.
+    ///
+    /// # Returns
+    /// * `u8` - Brightness from 0 to 255
+    #[allow(dead_code)]
+    pub fn dim_level(&self) -> u8 {
+        self.dim_level
+    }
+
+    /// Returns all-red clearance duration.
+    ///
+    /// # Details
+    /// Duration for the AllRed state in milliseconds.
+    /// Only used when the clearance phase is enabled.
+    ///
+    /// # Returns
+    /// * `u64` - All-red duration in milliseconds
+    #[allow(dead_code)]
+    pub fn all_red_duration(&self) -> u64 {
+        self.all_red_duration
+    }
+
+    /// Returns durations in whole seconds for display.
+    ///
+    /// # Details
+    /// Each duration is divided by 1000 and truncated.
+    ///
+    /// # Returns
+    /// * `(u32, u32, u32)` - Red, yellow and green durations in seconds
+    #[allow(dead_code)]
+    pub fn durations_seconds(&self) -> (u32, u32, u32) {
+        (
+            (self.red_duration / 1000) as u32,
+            (self.yellow_duration / 1000) as u32,
+            (self.green_duration / 1000) as u32,
+        )
+    }
+
+    /// Returns true if yellow is the shortest movement phase.
+    ///
+    /// # Details
+    /// Safety invariant: yellow must not outlast any other
+    /// movement phase. The AllRed clearance is not a movement
+    /// phase and is excluded. Ties count as shortest.
+    ///
+    /// # Returns
+    /// * `bool` - true if yellow <= every other movement phase
+    #[allow(dead_code)]
+    pub fn yellow_is_shortest(&self) -> bool {
+        [self.red_duration, self.green_duration]
+            .iter()
+            .all(|&other| self.yellow_duration <= other)
+    }
+
+    /// Returns the movement phase with the longest duration.
+    ///
+    /// # Details
+    /// Compares Red, Green and Yellow in cycle order; ties go to
+    /// the earlier phase, so Red wins any tie. AllRed is excluded.
+    ///
+    /// # Returns
+    /// * `TrafficLightState` - Longest phase
+    #[allow(dead_code)]
+    pub fn longest_phase(&self) -> TrafficLightState {
+        self.pick_phase(|candidate, best| candidate > best)
+    }
+
+    /// Returns the movement phase with the shortest duration.
+    ///
+    /// # Details
+    /// Compares Red, Green and Yellow in cycle order; ties go to
+    /// the earlier phase, so Red wins any tie. AllRed is excluded.
+    ///
+    /// # Returns
+    /// * `TrafficLightState` - Shortest phase
+    #[allow(dead_code)]
+    pub fn shortest_phase(&self) -> TrafficLightState {
+        self.pick_phase(|candidate, best| candidate < best)
+    }
+
+    /// Selects a movement phase by comparing durations.
+    ///
+    /// # Arguments
+    /// * `better` - Returns true if the candidate duration beats the best so far
+    ///
+    /// # Returns
+    /// * `TrafficLightState` - First phase in cycle order that no later phase beats
+    fn pick_phase(&self, better: impl Fn(u64, u64) -> bool) -> TrafficLightState {
+        [TrafficLightState::Green, TrafficLightState::Yellow]
+            .into_iter()
+            .fold(TrafficLightState::Red, |best, state| {
+                if better(self.duration_of(state), self.duration_of(best)) {
+                    state
+                } else {
+                    best
+                }
+            })
+    }
+
+    /// Returns true if red light should be on.
+    ///
+    /// # Details
+    /// Checks if current state is Red, the AllRed clearance or
+    /// the RedYellow get-ready phase. In fault mode follows the
+    /// flashing lamp.
+    ///
+    /// # Returns
+    /// * `bool` - true if red, false otherwise
+    #[allow(dead_code)]
+    pub fn is_red(&self) -> bool {
+        if self.is_fault() {
+            return self.flash_lamp_on;
+        }
+        matches!(
+            self.current_state,
+            TrafficLightState::Red | TrafficLightState::AllRed | TrafficLightState::RedYellow
+        )
+    }
+
+    /// Returns true if yellow light should be on.
+    ///
+    /// # Details
+    /// Checks if current state is Yellow or the RedYellow
+    /// get-ready phase. In night mode follows the flashing lamp.
+    ///
+    /// # Returns
+    /// * `bool` - true if yellow, false otherwise
+    #[allow(dead_code)]
+    pub fn is_yellow(&self) -> bool {
+        if self.is_night_mode() {
+            return self.flash_lamp_on;
+        }
+        matches!(
+            self.current_state,
+            TrafficLightState::Yellow | TrafficLightState::RedYellow
+        )
+    }
+
+    /// Returns true if green light should be on.
+    ///
+    /// # Details
+    /// Checks if current state is Green.
+    ///
+    /// # Returns
+    /// * `bool` - true if green, false otherwise
+    #[allow(dead_code)]
+    pub fn is_green(&self) -> bool {
+        self.current_state == TrafficLightState::Green
+    }
+
+    /// Returns true if a vehicle may enter the intersection.
+    ///
+    /// # Details
+    /// Single go/no-go decision for a vehicle stub: true only in
+    /// Green, including Green held by preemption. Yellow, Red and
+    /// the AllRed and RedYellow phases all return false.
+    ///
+    /// # Returns
+    /// * `bool` - true if green, false otherwise
+    #[allow(dead_code)]
+    pub fn is_safe_to_proceed(&self) -> bool {
+        self.current_state == TrafficLightState::Green
+    }
+
+    /// Returns true if a vehicle should prepare to stop.
+    ///
+    /// # Details
+    /// True only in Yellow, when the signal is about to turn red.
+    /// RedYellow lights the yellow lamp too but precedes Green,
+    /// so it returns false.
+    ///
+    /// # Returns
+    /// * `bool` - true if yellow, false otherwise
+    #[allow(dead_code)]
+    pub fn should_prepare_to_stop(&self) -> bool {
+        self.current_state == TrafficLightState::Yellow
+    }
+
+    /// Returns the GPIO levels for the three lamps.
+    ///
+    /// # Details
+    /// Single source of truth for driving all pins at once, built
+    /// from is_red(), is_yellow() and is_green(). One level is true,
+    /// except red and yellow together during RedYellow; all are
+    /// false only during the dark half of a flash.
+    ///
+    /// # Returns
+    /// * `(bool, bool, bool)` - (red_on, yellow_on, green_on)
+    #[allow(dead_code)]
+    pub fn pin_levels(&self) -> (bool, bool, bool) {
+        (self.is_red(), self.is_yellow(), self.is_green())
+    }
+
+    /// Returns a GRB frame for a three-pixel signal head.
+    ///
+    /// # Details
+    /// Pixels are ordered red, yellow, green, each as a (green,
+    /// red, blue) triple ready for a WS2812 strip. Lit lamps from
+    /// pin_levels() show their state_to_rgb() color at full
+    /// brightness; unlit lamps are dark.
+    ///
+    /// # Returns
+    /// * `[u8; 9]` - Three GRB triples
+    #[allow(dead_code)]
+    pub fn neopixel_frame(&self) -> [u8; 9] {
+        let (red_on, yellow_on, green_on) = self.pin_levels();
+        let lamps = [
+            (red_on, TrafficLightState::Red),
+            (yellow_on, TrafficLightState::Yellow),
+            (green_on, TrafficLightState::Green),
+        ];
+        let mut frame = [0u8; 9];
+        for (pixel, (on, state)) in frame.chunks_exact_mut(3).zip(lamps) {
+            if on {
+                let (r, g, b) = state_to_rgb(state);
+                pixel.copy_from_slice(&[g, r, b]);
+            }
+        }
+        frame
+    }
+
+    /// Returns the PWM duty for one lamp.
+    ///
+    /// # Details
+    /// The lamp is picked with state_to_pin_index(), so AllRed and
+    /// RedYellow address the red lamp. A lit lamp, per pin_levels(),
+    /// gets dim_level scaled onto 0..=PWM_TOP by led_level_to_duty();
+    /// an unlit lamp gets 0.
+    ///
+    /// # Arguments
+    /// * `state` - State whose lamp to drive
+    ///
+    /// # Returns
+    /// * `u16` - PWM compare value for the lamp
+    #[allow(dead_code)]
+    pub fn lamp_duty(&self, state: TrafficLightState) -> u16 {
+        let (red_on, yellow_on, green_on) = self.pin_levels();
+        if [red_on, yellow_on, green_on][state_to_pin_index(state)] {
+            led_level_to_duty(LedLevel::Dimmed(self.dim_level))
+        } else {
+            0
+        }
+    }
+
+    /// Returns true if the controller satisfies its safety invariants.
+    ///
+    /// # Details
+    /// Exactly one lamp must be lit in normal cycling or preemption,
+    /// or red and yellow together during RedYellow; flashing modes
+    /// may also show no lamp during the dark half of a flash.
+    /// Every duration must lie within MIN_DURATION_MS..=MAX_DURATION_MS.
+    /// Useful after deserialization or direct field changes.
+    ///
+    /// # Returns
+    /// * `bool` - true if the controller is in a valid state
+    #[allow(dead_code)]
+    pub fn is_valid(&self) -> bool {
+        let (r, y, g) = self.pin_levels();
+        let lit = r as u8 + y as u8 + g as u8;
+        let expected = if self.current_state == TrafficLightState::RedYellow {
+            2
+        } else {
+            1
+        };
+        let lamps_ok = match self.mode {
+            ControllerMode::Normal | ControllerMode::Preempted => lit == expected,
+            ControllerMode::FlashingRed | ControllerMode::FlashingYellow => lit <= 1,
+        };
+        let durations_ok = [
+            self.red_duration,
+            self.yellow_duration,
+            self.green_duration,
+            self.all_red_duration,
+            self.red_yellow_duration,
+            self.night_flash_duration,
+        ]
+        .into_iter()
+        .all(|duration| validate_duration(duration).is_ok());
+        lamps_ok && durations_ok
+    }
+
+    /// Returns the pedestrian signal for the current state.
+    ///
+    /// # Details
+    /// Pedestrians may only walk while traffic is held at Red.
+    /// During the last flashing_walk_ms of Red, by the elapsed time
+    /// tracked by tick(), the signal flashes so nobody starts to
+    /// cross. The AllRed clearance keeps pedestrians waiting as
+    /// well, as does a flashing red fault.
+    ///
+    /// # Returns
+    /// * `PedestrianSignal` - Walk or FlashingDontWalk if red, DontWalk otherwise
+    #[allow(dead_code)]
+    pub fn pedestrian_signal(&self) -> PedestrianSignal {
+        if self.current_state != TrafficLightState::Red || self.is_fault() {
+            PedestrianSignal::DontWalk
+        } else if self.flashing_walk_ms > 0
+            && self.remaining_ms(self.elapsed_in_phase) <= self.flashing_walk_ms
+        {
+            PedestrianSignal::FlashingDontWalk
+        } else {
+            PedestrianSignal::Walk
+        }
+    }
+
+    /// Sets the length of the flashing don't-walk countdown.
+    ///
+    /// # Details
+    /// pedestrian_signal() reports FlashingDontWalk for this long
+    /// at the end of each Red phase. 0 disables the countdown; a
+    /// value of at least the red duration flashes for all of Red.
+    ///
+    /// # Arguments
+    /// * `ms` - Countdown length in milliseconds
+    #[allow(dead_code)]
+    pub fn set_flashing_walk_ms(&mut self, ms: u64) {
+        self.flashing_walk_ms = ms;
+    }
+
+    /// Returns the length of the flashing don't-walk countdown.
+    ///
+    /// # Returns
+    /// * `u64` - Countdown length in milliseconds, 0 if disabled
+    #[allow(dead_code)]
+    pub fn flashing_walk_ms(&self) -> u64 {
+        self.flashing_walk_ms
+    }
+
+    /// Applies a complete timing plan atomically.
+    ///
+    /// # Details
+    /// Validates every duration in the plan before changing anything.
+    /// On failure the existing durations are left untouched.
+    ///
+    /// # Arguments
+    /// * `plan` - Timing plan to apply
+    ///
+    /// # Returns
+    /// * `Result<(), ConfigError>` - Ok if applied, error if the plan is invalid
+    #[allow(dead_code)]
+    pub fn apply_plan(&mut self, plan: TimingPlan) -> Result<(), ConfigError> {
+        plan.validate()?;
+        self.red_duration = plan.red_duration;
+        self.yellow_duration = plan.yellow_duration;
+        self.green_duration = plan.green_duration;
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Computes the green duration needed to serve a demand.
+    ///
+    /// # Details
+    /// Degree of saturation is X = (arrival * cycle) / (saturation * green),
+    /// so the green needed for a target X is:
+    /// green = arrival * cycle * 100 / (saturation * target_sat_pct),
+    /// rounded up and clamped into MIN_DURATION_MS..=MAX_DURATION_MS.
+    /// A zero saturation flow or target returns MAX_DURATION_MS.
+    ///
+    /// # Arguments
+    /// * `arrival_vph` - Arrival rate in vehicles per hour
+    /// * `saturation_vph` - Saturation flow while green in vehicles per hour
+    /// * `target_sat_pct` - Target degree of saturation in percent
+    ///
+    /// # Returns
+    /// * `u64` - Required green duration in milliseconds
+    #[allow(dead_code)]
+    pub fn green_for_demand(
+        &self,
+        arrival_vph: u32,
+        saturation_vph: u32,
+        target_sat_pct: u8,
+    ) -> u64 {
+        let capacity = saturation_vph as u64 * target_sat_pct as u64;
+        if capacity == 0 {
+            return MAX_DURATION_MS;
+        }
+        let demand = arrival_vph as u64 * self.total_cycle_duration() * 100;
+        demand
+            .div_ceil(capacity)
+            .clamp(MIN_DURATION_MS, MAX_DURATION_MS)
+    }
+
+    /// Returns true if demand exceeds green-time capacity.
+    ///
+    /// # Details
+    /// Capacity is saturation * green / cycle; the approach is
+    /// oversaturated when arrival > capacity, compared without
+    /// division as arrival * cycle > saturation * green.
+    ///
+    /// # Arguments
+    /// * `arrival_vph` - Arrival rate in vehicles per hour
+    /// * `saturation_vph` - Saturation flow while green in vehicles per hour
+    ///
+    /// # Returns
+    /// * `bool` - true if the intersection needs retiming
+    #[allow(dead_code)]
+    pub fn is_oversaturated(&self, arrival_vph: u32, saturation_vph: u32) -> bool {
+        arrival_vph as u64 * self.total_cycle_duration()
+            > saturation_vph as u64 * self.green_duration
+    }
+
+    /// Runs the controller forward and collects phase statistics.
+    ///
+    /// # Details
+    /// Calls tick(step_ms) repeatedly for n full cycles of time and
+    /// records how long each phase was observed to last. Observed
+    /// durations are quantized to the step, so they lie within one
+    /// step of the configured values. A phase already in progress
+    /// at the start is not recorded, nor is a phase skipped by a
+    /// step longer than it, nor the optional AllRed and RedYellow
+    /// phases, which PhaseStats has no slot for. A zero step is
+    /// treated as 1 ms.
+    ///
+    /// # Arguments
+    /// * `n` - Number of cycles to simulate
+    /// * `step_ms` - Tick resolution in milliseconds
+    ///
+    /// # Returns
+    /// * `PhaseStats` - Min/max/average observed duration per phase
+    #[allow(dead_code)]
+    pub fn simulate_cycles(&mut self, n: u32, step_ms: u64) -> PhaseStats {
+        let step = step_ms.max(1);
+        let end = n as u64 * self.total_cycle_duration();
+        let mut totals = [0u64; 3];
+        let mut stats = PhaseStats::default();
+        let mut now = 0;
+        let mut entered = (self.elapsed_in_phase == 0).then_some(0);
+        while now < end {
+            let state = self.current_state;
+            now += step;
+            if self.tick(step).is_none() || self.current_state == state {
+                continue;
+            }
+            let Some(started) = entered.replace(now) else {
+                continue;
+            };
+            let observed = now - started;
+            let (stat, total) = match state {
+                TrafficLightState::Red => (&mut stats.red, &mut totals[0]),
+                TrafficLightState::Yellow => (&mut stats.yellow, &mut totals[1]),
+                TrafficLightState::Green => (&mut stats.green, &mut totals[2]),
+                TrafficLightState::AllRed | TrafficLightState::RedYellow => continue,
+            };
+            stat.min_ms = if stat.samples == 0 {
+                observed
+            } else {
+                stat.min_ms.min(observed)
+            };
+            stat.max_ms = stat.max_ms.max(observed);
+            stat.samples += 1;
+            *total += observed;
+            stat.avg_ms = *total / stat.samples as u64;
+        }
+        stats
+    }
+
+    /// Scores the timing against throughput and delay goals.
+    ///
+    /// # Details
+    /// Throughput is the green-time capacity, saturation * green /
+    /// cycle, in vehicles per hour. Delay is the uniform delay of a
+    /// randomly arriving vehicle, non-green^2 / (2 * cycle), in
+    /// milliseconds. The score is
+    /// throughput_weight * throughput - delay_weight * delay,
+    /// floored at 0. Higher is better.
+    ///
+    /// # Arguments
+    /// * `throughput_weight` - Weight per vehicle per hour of capacity
+    /// * `delay_weight` - Penalty per millisecond of average delay
+    /// * `saturation_vph` - Saturation flow while green in vehicles per hour
+    ///
+    /// # Returns
+    /// * `u64` - Weighted score
+    #[allow(dead_code)]
+    pub fn cycle_score(
+        &self,
+        throughput_weight: u32,
+        delay_weight: u32,
+        saturation_vph: u32,
+    ) -> u64 {
+        let cycle = self.total_cycle_duration();
+        let throughput = saturation_vph as u64 * self.green_duration / cycle;
+        let non_green = cycle - self.green_duration;
+        let delay = non_green * non_green / (2 * cycle);
+        (throughput_weight as u64 * throughput).saturating_sub(delay_weight as u64 * delay)
+    }
+
+    /// Returns how long each lamp is lit during one cycle.
+    ///
+    /// # Details
+    /// The red lamp also covers the AllRed clearance, and both red
+    /// and yellow cover the RedYellow get-ready phase, when enabled.
+    ///
+    /// # Returns
+    /// * `[u64; 3]` - Red, yellow and green on-time in milliseconds
+    fn lamp_on_durations(&self) -> [u64; 3] {
+        let get_ready = self.get_ready_duration();
+        [
+            self.red_duration + self.clearance_duration() + get_ready,
+            self.yellow_duration + get_ready,
+            self.green_duration,
+        ]
+    }
+
+    /// Estimates each lamp's on-time over an operating period.
+    ///
+    /// # Details
+    /// Each lamp's share of the operating hours equals its share of
+    /// the cycle, from lamp_on_durations(). Results are truncated, so
+    /// they may sum slightly below the input, or above it while the
+    /// RedYellow phase lights two lamps at once.
+    ///
+    /// # Arguments
+    /// * `operating_hours` - Total operating period in hours
+    ///
+    /// # Returns
+    /// * `[u32; 3]` - On-hours for the red, yellow and green lamps
+    #[allow(dead_code)]
+    pub fn lamp_duty_hours(&self, operating_hours: u32) -> [u32; 3] {
+        let cycle = self.total_cycle_duration();
+        self.lamp_on_durations()
+            .map(|duration| (operating_hours as u64 * duration / cycle) as u32)
+    }
+
+    /// Computes the time-weighted average lamp power over a cycle.
+    ///
+    /// # Details
+    /// The average is each lamp's power weighted by its share of
+    /// the cycle, from lamp_on_durations(). The cycle repeats all
+    /// day, so this is also the daily average.
+    ///
+    /// # Arguments
+    /// * `per_lamp_mw` - Power draw of the red, yellow and green lamps in milliwatts
+    ///
+    /// # Returns
+    /// * `u32` - Average power in milliwatts, truncated
+    #[allow(dead_code)]
+    pub fn avg_power_mw(&self, per_lamp_mw: [u16; 3]) -> u32 {
+        let energy: u64 = self
+            .lamp_on_durations()
+            .iter()
+            .zip(per_lamp_mw)
+            .map(|(&duration, mw)| duration * mw as u64)
+            .sum();
+        (energy / self.total_cycle_duration()) as u32
+    }
+
+    /// Returns a state's share of the cycle as a percentage.
+    ///
+    /// # Details
+    /// Divides the state's duration by total_cycle_duration() and
+    /// rounds to the nearest percent, so the shares of a cycle sum
+    /// to about 100. AllRed and RedYellow give 0 while their
+    /// optional phase is disabled.
+    ///
+    /// # Arguments
+    /// * `state` - State to measure
+    ///
+    /// # Returns
+    /// * `u8` - Percentage of the cycle spent in the state, 0-100
+    #[allow(dead_code)]
+    pub fn duty_cycle(&self, state: TrafficLightState) -> u8 {
+        let duration = match state {
+            TrafficLightState::AllRed => self.clearance_duration(),
+            TrafficLightState::RedYellow => self.get_ready_duration(),
+            _ => self.duration_of(state),
+        } as u128;
+        let cycle = self.total_cycle_duration() as u128;
+        ((duration * 100 + cycle / 2) / cycle) as u8
+    }
+
+    /// Perturbs every duration with deterministic pseudo-random jitter.
+    ///
+    /// # Details
+    /// Uses a 32-bit linear congruential generator seeded with `seed`
+    /// to offset red, yellow and green by up to +/- `max_jitter_ms`.
+    /// Results are clamped into MIN_DURATION_MS..=MAX_DURATION_MS.
+    /// The same seed always produces the same durations.
+    ///
+    /// # Arguments
+    /// * `seed` - LCG seed
+    /// * `max_jitter_ms` - Maximum jitter magnitude in milliseconds
+    #[allow(dead_code)]
+    pub fn apply_jitter(&mut self, seed: u32, max_jitter_ms: u64) {
+        let mut lcg = seed;
+        let span = max_jitter_ms.saturating_mul(2).saturating_add(1);
+        for duration in [
+            &mut self.red_duration,
+            &mut self.yellow_duration,
+            &mut self.green_duration,
+        ] {
+            lcg = lcg.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let jittered = duration
+                .saturating_add(lcg as u64 % span)
+                .saturating_sub(max_jitter_ms);
+            *duration = jittered.clamp(MIN_DURATION_MS, MAX_DURATION_MS);
+        }
+        self.dirty = true;
+    }
+
+    /// Applies a duration diff produced by timing_diff().
+    ///
+    /// # Details
+    /// Each (phase_index, new_value) pair updates red (0), yellow (1)
+    /// or green (2). The diff is applied transactionally: any invalid
+    /// index or duration rejects the whole diff with no change.
+    ///
+    /// # Arguments
+    /// * `diff` - Changed phases with their new durations
+    ///
+    /// # Returns
+    /// * `Result<(), ConfigError>` - Ok if applied, error otherwise
+    #[allow(dead_code)]
+    pub fn apply_timing_diff(&mut self, diff: &[(u8, u64)]) -> Result<(), ConfigError> {
+        let mut plan = self.timing_plan();
+        for &(index, value) in diff {
+            match index {
+                0 => plan.red_duration = value,
+                1 => plan.yellow_duration = value,
+                2 => plan.green_duration = value,
+                _ => return Err(ConfigError::InvalidPhase(index)),
+            }
+        }
+        self.apply_plan(plan)
+    }
+
+    /// Returns the current durations as a timing plan.
+    ///
+    /// # Returns
+    /// * `TimingPlan` - Red, yellow and green durations
+    #[allow(dead_code)]
+    pub fn timing_plan(&self) -> TimingPlan {
+        TimingPlan {
+            red_duration: self.red_duration,
+            yellow_duration: self.yellow_duration,
+            green_duration: self.green_duration,
+        }
+    }
+
+    /// Returns true if the phase timer must be recomputed.
+    ///
+    /// # Details
+    /// Set by every timing setter and cleared by mark_scheduled().
+    ///
+    /// # Returns
+    /// * `bool` - true if timing changed since the last schedule
+    #[allow(dead_code)]
+    pub fn needs_reschedule(&self) -> bool {
+        self.dirty
+    }
+
+    /// Marks the current timing as scheduled.
+    ///
+    /// # Details
+    /// Call after arming the phase timer to clear the dirty flag.
+    #[allow(dead_code)]
+    pub fn mark_scheduled(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Packs the runtime state into a single register word.
+    ///
+    /// # Details
+    /// Only the runtime state is packed, not the durations.
+    /// Bit layout:
+    /// * bits 0-7 - state code (0 Red, 1 Yellow, 2 Green, 3 AllRed,
+    ///   4 RedYellow)
+    /// * bits 8-63 - elapsed time in the current phase in milliseconds,
+    ///   truncated to 56 bits
+    ///
+    /// # Returns
+    /// * `u64` - Packed runtime word
+    #[allow(dead_code)]
+    pub fn to_packed_word(self) -> u64 {
+        let code = u8::from(self.current_state) as u64;
+        (self.elapsed_in_phase << PACKED_STATE_BITS) | code
+    }
+
+    /// Restores a controller from a packed register word.
+    ///
+    /// # Details
+    /// Inverse of to_packed_word(). Durations take their default
+    /// values; an unknown state code restores as Red.
+    ///
+    /// # Arguments
+    /// * `word` - Packed runtime word
+    ///
+    /// # Returns
+    /// * `Self` - Controller with the packed state and elapsed time
+    #[allow(dead_code)]
+    pub fn from_packed_word(word: u64) -> Self {
+        let code = (word & PACKED_STATE_MASK) as u8;
+        let current_state = TrafficLightState::try_from(code).unwrap_or(TrafficLightState::Red);
+        Self {
+            current_state,
+            elapsed_in_phase: word >> PACKED_STATE_BITS,
+            ..Self::new()
+        }
+    }
+
+    /// Packs the signal status into a single byte.
+    ///
+    /// # Details
+    /// Sized for a one-byte CAN payload. Bit layout:
+    /// * bits 0-1 - state code (0 Red, 1 Yellow, 2 Green, 3 AllRed)
+    /// * bit 2 - fault mode (flashing red)
+    /// * bit 3 - night mode (flashing yellow)
+    /// * bit 4 - emergency preemption
+    /// * bit 5 - reserved, always 0
+    /// * bit 6 - RedYellow phase, sent with state code 0
+    /// * bit 7 - reserved, always 0
+    ///
+    /// RedYellow uses a previously reserved bit so bytes from a
+    /// controller without that phase decode exactly as before.
+    /// Decode with unpack_status().
+    ///
+    /// # Returns
+    /// * `u8` - Packed status byte
+    #[allow(dead_code)]
+    pub fn pack_status(&self) -> u8 {
+        let flags = match self.mode {
+            ControllerMode::Normal => 0,
+            ControllerMode::FlashingRed => STATUS_FAULT_BIT,
+            ControllerMode::FlashingYellow => STATUS_NIGHT_BIT,
+            ControllerMode::Preempted => STATUS_PREEMPT_BIT,
+        };
+        let state = match self.current_state {
+            TrafficLightState::RedYellow => {
+                u8::from(TrafficLightState::Red) | STATUS_RED_YELLOW_BIT
+            }
+            state => u8::from(state),
+        };
+        state | flags
+    }
+}
+
+/// Endless iterator over controller states.
+///
+/// # Details
+/// Returned by TrafficLightController::cycle(). Yields the
+/// current state, then each state advance() would produce.
+///
+/// # Fields
+/// * `cursor` - Private copy of the controller being stepped
+#[derive(Clone, Copy, Debug)]
+#[allow(dead_code)]
+pub struct StateCycle {
+    cursor: TrafficLightController,
+}
+
+impl Iterator for StateCycle {
+    type Item = TrafficLightState;
+
+    /// Yields the cursor state and advances the cursor.
+    ///
+    /// # Returns
+    /// * `Option<TrafficLightState>` - Always Some, the sequence never ends
+    fn next(&mut self) -> Option<Self::Item> {
+        let state = self.cursor.current_state;
+        self.cursor.advance();
+        Some(state)
+    }
+}
+
+/// Builder for TrafficLightController.
+///
+/// # Details
+/// Collects optional settings and validates them in build().
+/// Unset durations fall back to the config.rs constants.
+///
+/// # Fields
+/// * `red_duration` - Red duration in milliseconds, if set
+/// * `yellow_duration` - Yellow duration in milliseconds, if set
+/// * `green_duration` - Green duration in milliseconds, if set
+/// * `start_state` - State the controller starts in
+/// * `night_mode` - Start in night-mode flashing yellow
+/// * `red_yellow` - Insert the RedYellow get-ready phase before Green
+/// * `all_red` - Insert the AllRed clearance phase after Yellow
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct TrafficLightControllerBuilder {
+    red_duration: Option<u64>,
+    yellow_duration: Option<u64>,
+    green_duration: Option<u64>,
+    start_state: TrafficLightState,
+    night_mode: bool,
+    red_yellow: bool,
+    all_red: bool,
+}
+
+impl Default for TrafficLightControllerBuilder {
+    /// Returns default TrafficLightControllerBuilder instance.
+    ///
+    /// # Details
+    /// Delegates to new() for initialization.
+    ///
+    /// # Returns
+    /// * `Self` - New builder with default values
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrafficLightControllerBuilder {
+    /// Creates a builder with default settings.
+    ///
+    /// # Details
+    /// No durations set, starting at Red with night mode, the
+    /// get-ready phase and the clearance phase off.
+    ///
+    /// # Returns
+    /// * `Self` - New builder
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self {
+            red_duration: None,
+            yellow_duration: None,
+            green_duration: None,
+            start_state: TrafficLightState::Red,
+            night_mode: false,
+            red_yellow: false,
+            all_red: false,
+        }
+    }
+
+    /// Sets the red duration.
+    ///
+    /// # Arguments
+    /// * `duration` - Red duration in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the duration applied
+    #[allow(dead_code)]
+    pub fn red_duration(mut self, duration: u64) -> Self {
+        self.red_duration = Some(duration);
+        self
+    }
+
+    /// Sets the yellow duration.
+    ///
+    /// # Arguments
+    /// * `duration` - Yellow duration in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the duration applied
+    #[allow(dead_code)]
+    pub fn yellow_duration(mut self, duration: u64) -> Self {
+        self.yellow_duration = Some(duration);
+        self
+    }
+
+    /// Sets the green duration.
+    ///
+    /// # Arguments
+    /// * `duration` - Green duration in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the duration applied
+    #[allow(dead_code)]
+    pub fn green_duration(mut self, duration: u64) -> Self {
+        self.green_duration = Some(duration);
+        self
+    }
+
+    /// Sets the state the controller starts in.
+    ///
+    /// # Arguments
+    /// * `state` - Starting state
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the state applied
+    #[allow(dead_code)]
+    pub fn start_state(mut self, state: TrafficLightState) -> Self {
+        self.start_state = state;
+        self
+    }
+
+    /// Sets whether the controller starts in night mode.
+    ///
+    /// # Details
+    /// Night mode holds the state at Yellow, so it takes priority
+    /// over start_state().
+    ///
+    /// # Arguments
+    /// * `enabled` - true to start flashing yellow
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the flag applied
+    #[allow(dead_code)]
+    pub fn night_mode(mut self, enabled: bool) -> Self {
+        self.night_mode = enabled;
+        self
+    }
+
+    /// Enables the red+yellow get-ready phase.
+    ///
+    /// # Details
+    /// When enabled advance() inserts RedYellow between Red and
+    /// Green for RED_YELLOW_DURATION_MS, lighting the red and yellow
+    /// lamps together as required by some regional standards.
+    ///
+    /// # Arguments
+    /// * `enabled` - true to insert the get-ready phase
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the flag applied
+    #[allow(dead_code)]
+    pub fn red_yellow(mut self, enabled: bool) -> Self {
+        self.red_yellow = enabled;
+        self
+    }
+
+    /// Enables the all-red clearance phase.
+    ///
+    /// # Details
+    /// When enabled advance() inserts AllRed between Yellow and Red
+    /// for ALL_RED_DURATION_MS. Same as with_all_red() on the built
+    /// controller.
+    ///
+    /// # Arguments
+    /// * `enabled` - true to insert the clearance phase
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the flag applied
+    #[allow(dead_code)]
+    pub fn all_red(mut self, enabled: bool) -> Self {
+        self.all_red = enabled;
+        self
+    }
+
+    /// Validates the settings and builds the controller.
+    ///
+    /// # Details
+    /// Fills unset durations from RED_DURATION_MS, YELLOW_DURATION_MS
+    /// and GREEN_DURATION_MS, then checks the profile with
+    /// validate_durations().
+    ///
+    /// # Returns
+    /// * `Result<TrafficLightController, DurationError>` - Controller, or the failed check
+    #[allow(dead_code)]
+    pub fn build(self) -> Result<TrafficLightController, DurationError> {
+        let red = self.red_duration.unwrap_or(RED_DURATION_MS);
+        let yellow = self.yellow_duration.unwrap_or(YELLOW_DURATION_MS);
+        let green = self.green_duration.unwrap_or(GREEN_DURATION_MS);
+        validate_durations(red, yellow, green)?;
+        let mut controller = TrafficLightController {
+            current_state: self.start_state,
+            red_duration: red,
+            yellow_duration: yellow,
+            green_duration: green,
+            red_yellow_enabled: self.red_yellow,
+            all_red_enabled: self.all_red,
+            ..TrafficLightController::new()
+        };
+        controller.set_night_mode(self.night_mode);
+        controller.dirty = false;
+        Ok(controller)
+    }
+}
+
+/// Four-way intersection with two complementary approaches.
+///
+/// # Details
+/// Coordinates the north-south and east-west controllers so
+/// only one direction moves at a time. The moving direction
+/// steps through Green and Yellow (and AllRed if enabled); when
+/// it returns to Red the other direction is released to Green.
+/// The two directions are never both Green or both Yellow.
+///
+/// # Fields
+/// * `north_south` - Controller for the north-south approach
+/// * `east_west` - Controller for the east-west approach
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Intersection {
+    north_south: TrafficLightController,
+    east_west: TrafficLightController,
+}
+
+impl Default for Intersection {
+    /// Returns default Intersection instance.
+    ///
+    /// # Details
+    /// Delegates to new() for initialization.
+    ///
+    /// # Returns
+    /// * `Self` - New Intersection with default values
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Intersection {
+    /// Creates an intersection with north-south Green.
+    ///
+    /// # Details
+    /// North-south starts Green and east-west starts Red, both
+    /// with the default durations.
+    ///
+    /// # Returns
+    /// * `Self` - New Intersection instance
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self {
+            north_south: TrafficLightController::starting_at(TrafficLightState::Green),
+            east_west: TrafficLightController::starting_at(TrafficLightState::Red),
+        }
+    }
+
+    /// Steps the intersection to its next phase.
+    ///
+    /// # Details
+    /// Advances whichever direction is not held at Red. When that
+    /// direction reaches Red, the other direction advances to Green
+    /// in the same step. Debug builds assert that the new states
+    /// never show Green in both directions.
+    ///
+    /// # Returns
+    /// * `(TrafficLightState, TrafficLightState)` - New (north-south, east-west) states
+    #[allow(dead_code)]
+    pub fn advance(&mut self) -> (TrafficLightState, TrafficLightState) {
+        let (moving, waiting) = if self.north_south.current_state() != TrafficLightState::Red {
+            (&mut self.north_south, &mut self.east_west)
+        } else {
+            (&mut self.east_west, &mut self.north_south)
+        };
+        if moving.advance() == TrafficLightState::Red {
+            waiting.advance();
+        }
+        debug_assert!(!self.conflict_detected(), "both directions green");
+        self.states()
+    }
+
+    /// Returns the states of both approaches.
+    ///
+    /// # Returns
+    /// * `(TrafficLightState, TrafficLightState)` - (north-south, east-west) states
+    #[allow(dead_code)]
+    pub fn states(&self) -> (TrafficLightState, TrafficLightState) {
+        (
+            self.north_south.current_state(),
+            self.east_west.current_state(),
+        )
+    }
+
+    /// Returns true if both directions show Green at once.
+    ///
+    /// # Details
+    /// Checks the green lamps of both controllers. This conflict
+    /// must never occur; advance() debug-asserts against it and it
+    /// can be polled at runtime as a failsafe trigger.
+    ///
+    /// # Returns
+    /// * `bool` - true if both green lamps are lit
+    #[allow(dead_code)]
+    pub fn conflict_detected(&self) -> bool {
+        self.north_south.is_green() && self.east_west.is_green()
+    }
+
+    /// Returns true if the current state combination is safe.
+    ///
+    /// # Details
+    /// Unsafe combinations are both directions Green, as checked
+    /// by conflict_detected(), or both Yellow as checked by
+    /// both_yellow().
+    ///
+    /// # Returns
+    /// * `bool` - true if no conflicting combination is shown
+    #[allow(dead_code)]
+    pub fn is_safe(&self) -> bool {
+        let (ns, ew) = self.states();
+        !self.conflict_detected() && !both_yellow(ns, ew)
+    }
+}
+
+/// Two controllers held a fixed time apart.
+///
+/// # Details
+/// The leader runs on every tick. The follower holds its
+/// starting state until offset_ms of ticked time has passed and
+/// then runs on the same ticks, so it replays the leader's
+/// timeline delayed by the offset when both start alike.
+///
+/// # Fields
+/// * `leader` - Controller that runs immediately
+/// * `follower` - Controller delayed by the offset
+/// * `offset_ms` - Delay between leader and follower in milliseconds
+/// * `delay_remaining` - Offset time not yet consumed by ticks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct PhaseLockedPair {
+    leader: TrafficLightController,
+    follower: TrafficLightController,
+    offset_ms: u64,
+    delay_remaining: u64,
+}
+
+impl PhaseLockedPair {
+    /// Creates a phase-locked pair.
+    ///
+    /// # Arguments
+    /// * `leader` - Controller that runs immediately
+    /// * `follower` - Controller delayed by the offset
+    /// * `offset_ms` - Delay between leader and follower in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - New PhaseLockedPair instance
+    #[allow(dead_code)]
+    pub const fn new(
+        leader: TrafficLightController,
+        follower: TrafficLightController,
+        offset_ms: u64,
+    ) -> Self {
+        Self {
+            leader,
+            follower,
+            offset_ms,
+            delay_remaining: offset_ms,
+        }
+    }
+
+    /// Advances both controllers by elapsed time.
+    ///
+    /// # Details
+    /// Ticks the leader by the full elapsed time. The follower is
+    /// ticked only by the part of elapsed time left after the
+    /// outstanding offset has been consumed.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time elapsed since last tick in milliseconds
+    ///
+    /// # Returns
+    /// * `(TrafficLightState, TrafficLightState)` - New (leader, follower) states
+    #[allow(dead_code)]
+    pub fn tick(&mut self, elapsed_ms: u64) -> (TrafficLightState, TrafficLightState) {
+        self.leader.tick(elapsed_ms);
+        let delayed = elapsed_ms.min(self.delay_remaining);
+        self.delay_remaining -= delayed;
+        if elapsed_ms > delayed {
+            self.follower.tick(elapsed_ms - delayed);
+        }
+        self.states()
+    }
+
+    /// Returns the states of both controllers.
+    ///
+    /// # Returns
+    /// * `(TrafficLightState, TrafficLightState)` - (leader, follower) states
+    #[allow(dead_code)]
+    pub fn states(&self) -> (TrafficLightState, TrafficLightState) {
+        (self.leader.current_state(), self.follower.current_state())
+    }
+
+    /// Returns the leading controller.
+    ///
+    /// # Returns
+    /// * `&TrafficLightController` - Controller that runs immediately
+    #[allow(dead_code)]
+    pub fn leader(&self) -> &TrafficLightController {
+        &self.leader
+    }
+
+    /// Returns the following controller.
+    ///
+    /// # Returns
+    /// * `&TrafficLightController` - Controller delayed by the offset
+    #[allow(dead_code)]
+    pub fn follower(&self) -> &TrafficLightController {
+        &self.follower
+    }
+
+    /// Returns the configured offset.
+    ///
+    /// # Returns
+    /// * `u64` - Delay between leader and follower in milliseconds
+    #[allow(dead_code)]
+    pub fn offset_ms(&self) -> u64 {
+        self.offset_ms
+    }
+}
+
+/// Converts TrafficLightState to boolean for GPIO control.
+///
+/// # Details
+/// Maps specified state to true if current, false otherwise.
+///
+/// # Arguments
+/// * `current` - Current traffic light state
+/// * `target` - Target state to check
+///
+/// # Returns
+/// * `bool` - true if current matches target
+#[allow(dead_code)]
+pub fn state_to_level(current: TrafficLightState, target: TrafficLightState) -> bool {
+    current == target
+}
+
+/// Returns the index of the lamp lit for a state.
+///
+/// # Details
+/// Indexes a `[&mut Output; 3]` pin array ordered red, yellow,
+/// green. The AllRed clearance lights the red lamp. RedYellow
+/// lights two lamps, so it reports the red lamp; use lamp_levels()
+/// when every lit lamp matters.
+///
+/// # Arguments
+/// * `state` - Traffic light state
+///
+/// # Returns
+/// * `usize` - 0 for Red, 1 for Yellow, 2 for Green
+#[allow(dead_code)]
+pub fn state_to_pin_index(state: TrafficLightState) -> usize {
+    match state {
+        TrafficLightState::Red | TrafficLightState::AllRed | TrafficLightState::RedYellow => 0,
+        TrafficLightState::Yellow => 1,
+        TrafficLightState::Green => 2,
+    }
+}
+
+/// Returns the state whose lamp sits at a pin index.
+///
+/// # Details
+/// Inverse of state_to_pin_index(); index 0 maps to Red.
+///
+/// # Arguments
+/// * `idx` - Pin array index
+///
+/// # Returns
+/// * `Option<TrafficLightState>` - State for the index, None if out of range
+#[allow(dead_code)]
+pub fn pin_index_to_state(idx: usize) -> Option<TrafficLightState> {
+    match idx {
+        0 => Some(TrafficLightState::Red),
+        1 => Some(TrafficLightState::Yellow),
+        2 => Some(TrafficLightState::Green),
+        _ => None,
+    }
+}
+
+/// Selects which of the three lamps a state lights.
+///
+/// # Details
+/// Pure pin-selection logic behind TrafficLights::apply(), so it
+/// can be tested without hardware. Exactly one lamp is lit, except
+/// RedYellow which lights red and yellow; the AllRed clearance
+/// lights red.
+///
+/// # Arguments
+/// * `state` - Traffic light state to display
+///
+/// # Returns
+/// * `(bool, bool, bool)` - (red_on, yellow_on, green_on)
+#[allow(dead_code)]
+pub fn lamp_levels(state: TrafficLightState) -> (bool, bool, bool) {
+    match state {
+        TrafficLightState::Red | TrafficLightState::AllRed => (true, false, false),
+        TrafficLightState::Yellow => (false, true, false),
+        TrafficLightState::Green => (false, false, true),
+        TrafficLightState::RedYellow => (true, true, false),
+    }
+}
+
+/// Returns the RGB color shown for a state.
+///
+/// # Details
+/// For RGB pixels instead of discrete LEDs. Yellow uses the
+/// YELLOW_RGB amber; the AllRed clearance shows red. RedYellow
+/// blends red with amber into an orange.
+///
+/// # Arguments
+/// * `state` - Traffic light state
+///
+/// # Returns
+/// * `(u8, u8, u8)` - (red, green, blue) color components
+#[allow(dead_code)]
+pub fn state_to_rgb(state: TrafficLightState) -> (u8, u8, u8) {
+    match state {
+        TrafficLightState::Red | TrafficLightState::AllRed => (255, 0, 0),
+        TrafficLightState::Yellow => YELLOW_RGB,
+        TrafficLightState::Green => (0, 255, 0),
+        TrafficLightState::RedYellow => (
+            ((255 + YELLOW_RGB.0 as u16) / 2) as u8,
+            YELLOW_RGB.1 / 2,
+            YELLOW_RGB.2 / 2,
+        ),
+    }
+}
+
+/// Maps a state onto a two-lamp stop/go display.
+///
+/// # Details
+/// Red, AllRed and Yellow light the stop lamp, since traffic
+/// must not enter on any of them. Green lights the go lamp.
+/// Exactly one lamp is lit for every state.
+///
+/// # Arguments
+/// * `state` - Traffic light state to display
+///
+/// # Returns
+/// * `(bool, bool)` - (stop_lamp, go_lamp) levels
+#[allow(dead_code)]
+pub fn two_lamp_levels(state: TrafficLightState) -> (bool, bool) {
+    let go = state == TrafficLightState::Green;
+    (!go, go)
+}
+
+/// Maps a state onto a bicolor red/green LED pin pair.
+///
+/// # Details
+/// For modules with red and green sharing one bicolor LED and
+/// yellow on its own pin. Driving the anode high lights red,
+/// driving the cathode high reverses the current and lights
+/// green. Yellow holds both pins low so the bicolor LED is off;
+/// the yellow pin is driven with state_to_level() as usual.
+/// AllRed and RedYellow light red. The pins are never both high.
+///
+/// # Arguments
+/// * `state` - Traffic light state to display
+///
+/// # Returns
+/// * `(bool, bool)` - (anode, cathode) levels for the bicolor LED
+#[allow(dead_code)]
+pub fn bicolor_levels(state: TrafficLightState) -> (bool, bool) {
+    match state {
+        TrafficLightState::Red | TrafficLightState::AllRed | TrafficLightState::RedYellow => {
+            (true, false)
+        }
+        TrafficLightState::Green => (false, true),
+        TrafficLightState::Yellow => (false, false),
+    }
+}
+
+/// Returns the state that follows another in normal cycling.
+///
+/// # Details
+/// Looks the state up in TRANSITIONS. AllRed and RedYellow sit
+/// inside the Yellow -> Red and Red -> Green edges, so they
+/// finish the edge they were inserted into. Entering those
+/// optional phases is decided by advance(), which knows whether
+/// they are enabled.
+///
+/// # Arguments
+/// * `s` - Current state
+///
+/// # Returns
+/// * `TrafficLightState` - Next state in the base cycle
+#[allow(dead_code)]
+pub const fn next_state(s: TrafficLightState) -> TrafficLightState {
+    let source = match s {
+        TrafficLightState::AllRed => TrafficLightState::Yellow,
+        TrafficLightState::RedYellow => TrafficLightState::Red,
+        other => other,
+    };
+    let mut i = 0;
+    while i < TRANSITIONS.len() {
+        if TRANSITIONS[i].0 as u8 == source as u8 {
+            return TRANSITIONS[i].1;
+        }
+        i += 1;
+    }
+    // Unreachable while TRANSITIONS covers every base state; Red is the safe fallback.
+    TrafficLightState::Red
+}
+
+/// Returns true if a transition tells traffic to stop.
+///
+/// # Details
+/// Classifies Green -> Yellow and Yellow -> Red as stop events,
+/// e.g. to sound a warning buzzer. Yellow -> AllRed also counts,
+/// since the red lamp comes on there when the clearance phase is
+/// enabled. Same-state pairs are never transitions.
+///
+/// # Arguments
+/// * `from` - State before the transition
+/// * `to` - State after the transition
+///
+/// # Returns
+/// * `bool` - true for a stopping transition
+#[allow(dead_code)]
+pub fn is_stopping_transition(from: TrafficLightState, to: TrafficLightState) -> bool {
+    matches!(
+        (from, to),
+        (TrafficLightState::Green, TrafficLightState::Yellow)
+            | (TrafficLightState::Yellow, TrafficLightState::Red)
+            | (TrafficLightState::Yellow, TrafficLightState::AllRed)
+    )
+}
+
+/// Returns true if a transition releases traffic.
+///
+/// # Details
+/// Classifies Red -> Green as a go event. RedYellow -> Green also
+/// counts, since Green follows the get-ready phase when enabled.
+///
+/// # Arguments
+/// * `from` - State before the transition
+/// * `to` - State after the transition
+///
+/// # Returns
+/// * `bool` - true for a go transition
+#[allow(dead_code)]
+pub fn is_go_transition(from: TrafficLightState, to: TrafficLightState) -> bool {
+    matches!(
+        (from, to),
+        (TrafficLightState::Red, TrafficLightState::Green)
+            | (TrafficLightState::RedYellow, TrafficLightState::Green)
+    )
+}
+
+/// Returns true if two approaches both show Yellow.
+///
+/// # Details
+/// Both directions clearing at once means neither has a safe
+/// right of way to follow, so this combination is treated as
+/// an unsafe intersection state.
+///
+/// # Arguments
+/// * `a` - State of the first approach
+/// * `b` - State of the second approach
+///
+/// # Returns
+/// * `bool` - true if both states are Yellow
+#[allow(dead_code)]
+pub fn both_yellow(a: TrafficLightState, b: TrafficLightState) -> bool {
+    a == TrafficLightState::Yellow && b == TrafficLightState::Yellow
+}
+
+/// Counts how many distinct states appear in a log.
+///
+/// # Details
+/// Used for coverage analysis to confirm every phase was exercised,
+/// including the optional AllRed and RedYellow phases.
+///
+/// # Arguments
+/// * `states` - Logged states in any order
+///
+/// # Returns
+/// * `u8` - Number of unique states present (0-5)
+#[allow(dead_code)]
+pub fn distinct_states(states: &[TrafficLightState]) -> u8 {
+    [
+        TrafficLightState::Red,
+        TrafficLightState::Yellow,
+        TrafficLightState::Green,
+        TrafficLightState::AllRed,
+        TrafficLightState::RedYellow,
+    ]
+    .iter()
+    .filter(|state| states.contains(state))
+    .count() as u8
+}
+
+/// Computes the minimal set of duration changes between two configs.
+///
+/// # Details
+/// Lists only phases whose duration differs, as (phase_index, new_value)
+/// pairs where index 0 is red, 1 is yellow and 2 is green.
+/// Identical configurations produce an empty diff.
+///
+/// # Arguments
+/// * `old` - Currently deployed controller configuration
+/// * `new` - Target controller configuration
+///
+/// # Returns
+/// * `heapless::Vec<(u8, u64), 3>` - Changed phases with their new durations
+#[allow(dead_code)]
+pub fn timing_diff(
+    old: &TrafficLightController,
+    new: &TrafficLightController,
+) -> heapless::Vec<(u8, u64), 3> {
+    let old_durations = [old.red_duration, old.yellow_duration, old.green_duration];
+    let new_durations = [new.red_duration, new.yellow_duration, new.green_duration];
+    let mut diff = heapless::Vec::new();
+    for (index, (old_ms, new_ms)) in old_durations.iter().zip(new_durations).enumerate() {
+        if *old_ms != new_ms {
+            let _ = diff.push((index as u8, new_ms));
+        }
+    }
+    diff
+}
+
+// ==================== Coordination ====================
+
+/// Computes the green-wave start offset for a downstream light.
+///
+/// # Details
+/// Travel time from the upstream light at the progression speed,
+/// distance * 1000 / speed, truncated. The product is computed in
+/// u64 so it cannot overflow, and a zero speed returns 0 rather
+/// than dividing by zero.
+///
+/// # Arguments
+/// * `distance_m` - Distance from the upstream light in meters
+/// * `speed_mps` - Progression speed in meters per second
+///
+/// # Returns
+/// * `u64` - Start offset in milliseconds, 0 for zero speed
+#[allow(dead_code)]
+pub fn green_wave_offset(distance_m: u32, speed_mps: u32) -> u64 {
+    (distance_m as u64 * 1000)
+        .checked_div(speed_mps as u64)
+        .unwrap_or(0)
+}
+
+/// Computes green-wave offsets for every intersection in a corridor.
+///
+/// # Details
+/// The first intersection has offset 0 and each following one is
+/// offset by the cumulative travel time, wrapped by the cycle length.
+/// `out` is cleared first; entries beyond its capacity are dropped.
+/// A zero cycle leaves the offsets unwrapped.
+///
+/// # Arguments
+/// * `travel_ms` - Travel time between consecutive intersections in milliseconds
+/// * `cycle_ms` - Common cycle length in milliseconds
+/// * `out` - Receives one offset per intersection
+#[allow(dead_code)]
+pub fn corridor_offsets<const N: usize>(
+    travel_ms: &[u64],
+    cycle_ms: u64,
+    out: &mut heapless::Vec<u64, N>,
+) {
+    out.clear();
+    let mut cumulative: u64 = 0;
+    if out.push(0).is_err() {
+        return;
+    }
+    for travel in travel_ms {
+        cumulative = cumulative.saturating_add(*travel);
+        let offset = cumulative.checked_rem(cycle_ms).unwrap_or(cumulative);
+        if out.push(offset).is_err() {
+            return;
+        }
+    }
+}
+
+/// Computes the phase offset between two controllers.
+///
+/// # Details
+/// Returns how far `a` leads `b` within the cycle, wrapped into
+/// 0..cycle so a lagging controller appears as a large lead.
+/// Assumes both controllers use the same cycle length.
+///
+/// # Arguments
+/// * `a` - Reference controller
+/// * `b` - Controller to compare against
+///
+/// # Returns
+/// * `u64` - Modular difference of within-cycle positions in milliseconds
+#[allow(dead_code)]
+pub fn phase_difference_ms(a: &TrafficLightController, b: &TrafficLightController) -> u64 {
+    let cycle = a.total_cycle_duration();
+    let a_pos = a.ms_since_cycle_start() % cycle;
+    let b_pos = b.ms_since_cycle_start() % cycle;
+    (a_pos + cycle - b_pos) % cycle
+}
+
+/// Estimates the share of a platoon that clears every green in a corridor.
+///
+/// # Details
+/// Simplifying assumptions:
+/// * The platoon is 100 evenly spaced vehicles released across the
+///   first controller's green, starting from its cycle start at t=0.
+/// * `offsets[i]` is when controller `i` starts Red relative to t=0.
+/// * `travel_ms[i]` is the travel time from controller `i` to `i + 1`;
+///   vehicles travel at constant speed with no queueing.
+/// * A vehicle passes if it arrives while each downstream light is Green.
+///
+/// Extra offsets or travel times beyond the controllers are ignored.
+/// Arrival times saturate at u64::MAX instead of overflowing.
+///
+/// # Arguments
+/// * `controllers` - Controllers along the corridor in travel order
+/// * `offsets` - Cycle start offset of each controller in milliseconds
+/// * `travel_ms` - Travel time between consecutive controllers in milliseconds
+///
+/// # Returns
+/// * `u32` - Percentage (0-100) of the platoon that passes, 0 if empty
+#[allow(dead_code)]
+pub fn corridor_utilization(
+    controllers: &[TrafficLightController],
+    offsets: &[u64],
+    travel_ms: &[u64],
+) -> u32 {
+    const PLATOON_SIZE: u64 = 100;
+    let Some(first) = controllers.first() else {
+        return 0;
+    };
+    let green_start = first.phase_start_offset(TrafficLightState::Green);
+    let mut passed = 0;
+    for vehicle in 0..PLATOON_SIZE {
+        let mut arrival = green_start + vehicle * first.green_duration / PLATOON_SIZE;
+        let mut clear = true;
+        let downstream = controllers.iter().zip(offsets).skip(1).zip(travel_ms);
+        for ((ctrl, offset), travel) in downstream {
+            arrival = arrival.saturating_add(*travel);
+            let cycle = ctrl.total_cycle_duration();
+            let local = arrival % cycle + cycle - offset % cycle;
+            if ctrl.phase_at_offset(local) != TrafficLightState::Green {
+                clear = false;
+                break;
+            }
+        }
+        if clear {
+            passed += 1;
+        }
+    }
+    passed
+}
+
+/// Decodes a status byte produced by pack_status().
+///
+/// # Details
+/// Rejects bytes with reserved bits set, more than one mode flag
+/// (a controller is only ever in one mode), or the RedYellow bit
+/// paired with any state code but Red.
+///
+/// # Arguments
+/// * `byte` - Packed status byte
+///
 /// # Returns
-/// * `bool` - true if current matches target
+/// * `Result<(TrafficLightState, StatusFlags), StatusError>` - State and flags, or the rejected byte
 #[allow(dead_code)]
-pub fn state_to_level(current: TrafficLightState, target: TrafficLightState) -> bool {
-    current == target
+pub fn unpack_status(byte: u8) -> Result<(TrafficLightState, StatusFlags), StatusError> {
+    if byte & STATUS_RESERVED_MASK != 0 {
+        return Err(StatusError::ReservedBits(byte));
+    }
+    let flags = StatusFlags {
+        fault: byte & STATUS_FAULT_BIT != 0,
+        night: byte & STATUS_NIGHT_BIT != 0,
+        preempt: byte & STATUS_PREEMPT_BIT != 0,
+    };
+    if flags.fault as u8 + flags.night as u8 + flags.preempt as u8 > 1 {
+        return Err(StatusError::ConflictingFlags(byte));
+    }
+    let code = byte & STATUS_STATE_MASK;
+    let state = if byte & STATUS_RED_YELLOW_BIT == 0 {
+        TrafficLightState::try_from(code).map_err(|_| StatusError::InvalidState(byte))?
+    } else if code == u8::from(TrafficLightState::Red) {
+        TrafficLightState::RedYellow
+    } else {
+        return Err(StatusError::InvalidState(byte));
+    };
+    Ok((state, flags))
+}
+
+/// Group of the three traffic light LED outputs.
+///
+/// # Details
+/// Owns the red, yellow and green pins so they can be driven
+/// together from a state instead of one by one.
+///
+/// # Fields
+/// * `red` - Red LED GPIO output
+/// * `yellow` - Yellow LED GPIO output
+/// * `green` - Green LED GPIO output
+#[cfg(feature = "embassy-rp")]
+#[allow(dead_code)]
+pub struct TrafficLights<'a> {
+    red: Output<'a>,
+    yellow: Output<'a>,
+    green: Output<'a>,
+}
+
+#[cfg(feature = "embassy-rp")]
+impl<'a> TrafficLights<'a> {
+    /// Creates an LED group from the three outputs.
+    ///
+    /// # Arguments
+    /// * `red` - Red LED GPIO output
+    /// * `yellow` - Yellow LED GPIO output
+    /// * `green` - Green LED GPIO output
+    ///
+    /// # Returns
+    /// * `Self` - New TrafficLights instance
+    #[allow(dead_code)]
+    pub fn new(red: Output<'a>, yellow: Output<'a>, green: Output<'a>) -> Self {
+        Self { red, yellow, green }
+    }
+
+    /// Lights exactly the lamp for a state.
+    ///
+    /// # Details
+    /// Uses lamp_levels() to pick the lamp and set_levels() to
+    /// drive the pins.
+    ///
+    /// # Arguments
+    /// * `state` - Traffic light state to display
+    #[allow(dead_code)]
+    pub fn apply(&mut self, state: TrafficLightState) {
+        self.set_levels(lamp_levels(state));
+    }
+
+    /// Drives the three lamps to the given levels.
+    ///
+    /// # Details
+    /// Writes each lamp once: the unlit lamps are switched off in a
+    /// first pass and the lit ones on in a second, so two LEDs are
+    /// never on at once during a change. Takes
+    /// the tuple from lamp_levels() or the controller's
+    /// pin_levels(), which also covers flashing modes.
+    ///
+    /// # Arguments
+    /// * `levels` - (red, yellow, green) levels, true for on
+    #[allow(dead_code)]
+    pub fn set_levels(&mut self, levels: (bool, bool, bool)) {
+        let (r, y, g) = levels;
+        if !r {
+            set_led(&mut self.red, false);
+        }
+        if !y {
+            set_led(&mut self.yellow, false);
+        }
+        if !g {
+            set_led(&mut self.green, false);
+        }
+        if r {
+            set_led(&mut self.red, true);
+        }
+        if y {
+            set_led(&mut self.yellow, true);
+        }
+        if g {
+            set_led(&mut self.green, true);
+        }
+    }
+
+    /// Switches all three lamps off.
+    #[allow(dead_code)]
+    pub fn all_off(&mut self) {
+        set_led(&mut self.red, false);
+        set_led(&mut self.yellow, false);
+        set_led(&mut self.green, false);
+    }
+}
+
+/// Drives the traffic light LEDs forever.
+///
+/// # Details
+/// Each iteration passes the controller's pin_levels() to
+/// TrafficLights::set_levels(), then waits current_duration() and
+/// advances the controller.
+///
+/// # Arguments
+/// * `controller` - Controller providing state and timing
+/// * `lights` - Red, yellow and green LED outputs
+///
+/// # Returns
+/// * `!` - Never returns
+#[cfg(all(feature = "embassy-rp", feature = "embassy-time"))]
+#[allow(dead_code)]
+pub async fn run_traffic_light(
+    controller: &mut TrafficLightController,
+    lights: &mut TrafficLights<'_>,
+) -> ! {
+    loop {
+        lights.set_levels(controller.pin_levels());
+        Timer::after_millis(controller.current_duration()).await;
+        controller.advance();
+    }
+}
+
+/// Decides whether a button edge is a real press.
+///
+/// # Details
+/// The button is sampled again after the edge; the press counts
+/// only if at least `window_ms` has passed and the button is still
+/// held. Bounces, including those on release, fail the check.
+///
+/// # Arguments
+/// * `edge_ms` - Time of the falling edge in milliseconds
+/// * `sample_ms` - Time of the confirming sample in milliseconds
+/// * `still_pressed` - Button level at the confirming sample
+/// * `window_ms` - Debounce window in milliseconds
+///
+/// # Returns
+/// * `bool` - true if the edge is accepted as a press
+#[allow(dead_code)]
+pub fn debounce_accepts(edge_ms: u64, sample_ms: u64, still_pressed: bool, window_ms: u64) -> bool {
+    still_pressed && sample_ms.saturating_sub(edge_ms) >= window_ms
+}
+
+/// Waits for a debounced button press and advances the controller.
+///
+/// # Details
+/// Waits for a falling edge (button pulled low when pressed), lets
+/// BUTTON_DEBOUNCE_MS pass and confirms the press with
+/// debounce_accepts() before calling advance().
+///
+/// # Arguments
+/// * `controller` - Controller to step
+/// * `button` - Push-button GPIO input, active low
+///
+/// # Returns
+/// * `TrafficLightState` - New state after the press
+#[cfg(all(feature = "embassy-rp", feature = "embassy-time"))]
+#[allow(dead_code)]
+pub async fn advance_on_button(
+    controller: &mut TrafficLightController,
+    button: &mut Input<'_>,
+) -> TrafficLightState {
+    loop {
+        button.wait_for_falling_edge().await;
+        let edge_ms = Instant::now().as_millis();
+        Timer::after_millis(BUTTON_DEBOUNCE_MS).await;
+        let sample_ms = Instant::now().as_millis();
+        if debounce_accepts(edge_ms, sample_ms, button.is_low(), BUTTON_DEBOUNCE_MS) {
+            return controller.advance();
+        }
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BUTTON_DEBOUNCE_MS;
+
+    // ==================== TrafficLightState Enum Tests ====================
+
+    #[test]
+    fn test_state_red_exists() {
+        let _state = TrafficLightState::Red;
+    }
+
+    #[test]
+    fn test_state_yellow_exists() {
+        let _state = TrafficLightState::Yellow;
+    }
+
+    #[test]
+    fn test_state_green_exists() {
+        let _state = TrafficLightState::Green;
+    }
+
+    #[test]
+    fn test_state_equality_red() {
+        assert_eq!(TrafficLightState::Red, TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_state_equality_yellow() {
+        assert_eq!(TrafficLightState::Yellow, TrafficLightState::Yellow);
+    }
+
+    #[test]
+    fn test_state_equality_green() {
+        assert_eq!(TrafficLightState::Green, TrafficLightState::Green);
+    }
+
+    #[test]
+    fn test_state_inequality_red_yellow() {
+        assert_ne!(TrafficLightState::Red, TrafficLightState::Yellow);
+    }
+
+    #[test]
+    fn test_state_inequality_red_green() {
+        assert_ne!(TrafficLightState::Red, TrafficLightState::Green);
+    }
+
+    #[test]
+    fn test_state_inequality_yellow_green() {
+        assert_ne!(TrafficLightState::Yellow, TrafficLightState::Green);
+    }
+
+    #[test]
+    fn test_state_copy() {
+        let state = TrafficLightState::Red;
+        let copy = state;
+        assert_eq!(state, copy);
+    }
+
+    #[test]
+    fn test_state_clone() {
+        let state = TrafficLightState::Green;
+        #[allow(clippy::clone_on_copy)]
+        let cloned = state.clone();
+        assert_eq!(state, cloned);
+    }
+
+    #[test]
+    fn test_state_debug_red() {
+        let debug_str = format!("{:?}", TrafficLightState::Red);
+        assert_eq!(debug_str, "Red");
+    }
+
+    #[test]
+    fn test_state_debug_yellow() {
+        let debug_str = format!("{:?}", TrafficLightState::Yellow);
+        assert_eq!(debug_str, "Yellow");
+    }
+
+    #[test]
+    fn test_state_debug_green() {
+        let debug_str = format!("{:?}", TrafficLightState::Green);
+        assert_eq!(debug_str, "Green");
+    }
+
+    #[test]
+    fn test_state_size() {
+        assert_eq!(core::mem::size_of::<TrafficLightState>(), 1);
+    }
+
+    // ==================== state_to_level Function Tests ====================
+
+    #[test]
+    fn test_state_to_level_red_match() {
+        assert!(state_to_level(
+            TrafficLightState::Red,
+            TrafficLightState::Red
+        ));
+    }
+
+    #[test]
+    fn test_state_to_level_yellow_match() {
+        assert!(state_to_level(
+            TrafficLightState::Yellow,
+            TrafficLightState::Yellow
+        ));
+    }
+
+    #[test]
+    fn test_state_to_level_green_match() {
+        assert!(state_to_level(
+            TrafficLightState::Green,
+            TrafficLightState::Green
+        ));
+    }
+
+    #[test]
+    fn test_state_to_level_red_no_match() {
+        assert!(!state_to_level(
+            TrafficLightState::Red,
+            TrafficLightState::Green
+        ));
+    }
+
+    #[test]
+    fn test_state_to_level_yellow_no_match() {
+        assert!(!state_to_level(
+            TrafficLightState::Yellow,
+            TrafficLightState::Red
+        ));
+    }
+
+    #[test]
+    fn test_state_to_level_green_no_match() {
+        assert!(!state_to_level(
+            TrafficLightState::Green,
+            TrafficLightState::Yellow
+        ));
+    }
+
+    // ==================== TrafficLightController::new() Tests ====================
+
+    #[test]
+    fn test_new_controller() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.red_duration(), RED_DURATION_MS);
+    }
+
+    #[test]
+    fn test_new_controller_starts_at_red() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_new_controller_yellow_duration() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.yellow_duration(), YELLOW_DURATION_MS);
+    }
+
+    #[test]
+    fn test_new_controller_green_duration() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.green_duration(), GREEN_DURATION_MS);
+    }
+
+    #[test]
+    fn test_new_returns_consistent_value() {
+        let ctrl1 = TrafficLightController::new();
+        let ctrl2 = TrafficLightController::new();
+        assert_eq!(ctrl1, ctrl2);
+    }
+
+    // ==================== TrafficLightController::default() Tests ====================
+
+    #[test]
+    fn test_default_equals_new() {
+        let default = TrafficLightController::default();
+        let new = TrafficLightController::new();
+        assert_eq!(default, new);
+    }
+
+    #[test]
+    fn test_default_starts_at_red() {
+        let default = TrafficLightController::default();
+        assert_eq!(default.current_state(), TrafficLightState::Red);
+    }
+
+    // ==================== TrafficLightController::advance() Tests ====================
+
+    #[test]
+    fn test_advance_from_red() {
+        let mut ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.advance(), TrafficLightState::Green);
+    }
+
+    #[test]
+    fn test_advance_from_green() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        assert_eq!(ctrl.advance(), TrafficLightState::Yellow);
+    }
+
+    #[test]
+    fn test_advance_from_yellow() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        ctrl.advance();
+        assert_eq!(ctrl.advance(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_advance_returns_new_state() {
+        let mut ctrl = TrafficLightController::new();
+        let new_state = ctrl.advance();
+        assert_eq!(new_state, ctrl.current_state());
+    }
+
+    #[test]
+    fn test_advance_full_cycle() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        ctrl.advance();
+        ctrl.advance();
+        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_advance_multiple_cycles() {
+        let mut ctrl = TrafficLightController::new();
+        for _ in 0..9 {
+            ctrl.advance();
+        }
+        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+    }
+
+    // ==================== TrafficLightController::current_duration() Tests ====================
+
+    #[test]
+    fn test_current_duration_red() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.current_duration(), RED_DURATION_MS);
+    }
+
+    #[test]
+    fn test_current_duration_green() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        assert_eq!(ctrl.current_duration(), GREEN_DURATION_MS);
+    }
+
+    #[test]
+    fn test_current_duration_yellow() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        ctrl.advance();
+        assert_eq!(ctrl.current_duration(), YELLOW_DURATION_MS);
+    }
+
+    // ==================== TrafficLightController::is_red() Tests ====================
+
+    #[test]
+    fn test_is_red_initial() {
+        let ctrl = TrafficLightController::new();
+        assert!(ctrl.is_red());
+    }
+
+    #[test]
+    fn test_is_red_after_advance() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        assert!(!ctrl.is_red());
+    }
+
+    // ==================== TrafficLightController::is_yellow() Tests ====================
+
+    #[test]
+    fn test_is_yellow_initial() {
+        let ctrl = TrafficLightController::new();
+        assert!(!ctrl.is_yellow());
+    }
+
+    #[test]
+    fn test_is_yellow_after_two_advances() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        ctrl.advance();
+        assert!(ctrl.is_yellow());
+    }
+
+    // ==================== TrafficLightController::is_green() Tests ====================
+
+    #[test]
+    fn test_is_green_initial() {
+        let ctrl = TrafficLightController::new();
+        assert!(!ctrl.is_green());
+    }
+
+    #[test]
+    fn test_is_green_after_advance() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        assert!(ctrl.is_green());
+    }
+
+    // ==================== State Exclusivity Tests ====================
+
+    #[test]
+    fn test_only_one_state_active_red() {
+        let ctrl = TrafficLightController::new();
+        assert!(ctrl.is_red() && !ctrl.is_yellow() && !ctrl.is_green());
+    }
+
+    #[test]
+    fn test_only_one_state_active_green() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        assert!(!ctrl.is_red() && !ctrl.is_yellow() && ctrl.is_green());
+    }
+
+    #[test]
+    fn test_only_one_state_active_yellow() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        ctrl.advance();
+        assert!(!ctrl.is_red() && ctrl.is_yellow() && !ctrl.is_green());
+    }
+
+    // ==================== Trait Implementation Tests ====================
+
+    #[test]
+    fn test_controller_clone() {
+        let ctrl1 = TrafficLightController::new();
+        let ctrl2 = ctrl1;
+        assert_eq!(ctrl1.red_duration(), ctrl2.red_duration());
+    }
+
+    #[test]
+    fn test_controller_copy() {
+        let ctrl1 = TrafficLightController::new();
+        let ctrl2 = ctrl1;
+        assert_eq!(ctrl1, ctrl2);
+    }
+
+    #[test]
+    fn test_controller_partial_eq() {
+        let ctrl1 = TrafficLightController::new();
+        let ctrl2 = TrafficLightController::new();
+        assert_eq!(ctrl1, ctrl2);
+    }
+
+    #[test]
+    fn test_controller_debug() {
+        let ctrl = TrafficLightController::new();
+        let debug_str = format!("{:?}", ctrl);
+        assert!(debug_str.contains("TrafficLightController"));
+    }
+
+    #[test]
+    fn test_controller_debug_contains_state() {
+        let ctrl = TrafficLightController::new();
+        let debug_str = format!("{:?}", ctrl);
+        assert!(debug_str.contains("Red"));
+    }
+
+    // ==================== State Transition Tests ====================
+
+    #[test]
+    fn test_sequence_cycle_red_to_green() {
+        let mut ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+        ctrl.advance();
+        assert_eq!(ctrl.current_state(), TrafficLightState::Green);
+    }
+
+    #[test]
+    fn test_sequence_cycle_green_to_yellow() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        ctrl.advance();
+        assert_eq!(ctrl.current_state(), TrafficLightState::Yellow);
+    }
+
+    #[test]
+    fn test_sequence_cycle_yellow_to_red() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        ctrl.advance();
+        ctrl.advance();
+        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_independent_controllers() {
+        let mut ctrl1 = TrafficLightController::new();
+        let ctrl2 = TrafficLightController::new();
+        ctrl1.advance();
+        assert_eq!(ctrl2.current_state(), TrafficLightState::Red);
+        assert_eq!(ctrl1.current_state(), TrafficLightState::Green);
+    }
+
+    // ==================== Edge Case Tests ====================
+
+    #[test]
+    fn test_many_controllers() {
+        let controllers: Vec<TrafficLightController> =
+            (0..100).map(|_| TrafficLightController::new()).collect();
+        for ctrl in controllers {
+            assert_eq!(ctrl.red_duration(), RED_DURATION_MS);
+        }
+    }
+
+    #[test]
+    fn test_controller_in_option() {
+        let maybe_ctrl: Option<TrafficLightController> = Some(TrafficLightController::new());
+        assert!(maybe_ctrl.is_some());
+        assert_eq!(maybe_ctrl.unwrap().red_duration(), RED_DURATION_MS);
+    }
+
+    #[test]
+    fn test_controller_in_result() {
+        let result: Result<TrafficLightController, ()> = Ok(TrafficLightController::new());
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().red_duration(), RED_DURATION_MS);
+    }
+
+    #[test]
+    fn test_advance_100_times() {
+        let mut ctrl = TrafficLightController::new();
+        for _ in 0..99 {
+            ctrl.advance();
+        }
+        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_controller_size() {
+        assert!(core::mem::size_of::<TrafficLightController>() <= 128);
+    }
+
+    #[test]
+    fn test_controller_alignment() {
+        assert!(core::mem::align_of::<TrafficLightController>() <= 8);
+    }
+
+    // ==================== TrafficLightController::apply_plan() Tests ====================
+
+    #[test]
+    fn test_apply_plan_valid() {
+        let mut ctrl = TrafficLightController::new();
+        let plan = TimingPlan {
+            red_duration: 5000,
+            yellow_duration: 500,
+            green_duration: 4000,
+        };
+        assert_eq!(ctrl.apply_plan(plan), Ok(()));
+        assert_eq!(ctrl.red_duration(), 5000);
+        assert_eq!(ctrl.yellow_duration(), 500);
+        assert_eq!(ctrl.green_duration(), 4000);
+    }
+
+    #[test]
+    fn test_apply_plan_keeps_state() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        ctrl.apply_plan(TimingPlan::default()).unwrap();
+        assert_eq!(ctrl.current_state(), TrafficLightState::Green);
+    }
+
+    #[test]
+    fn test_apply_plan_invalid_leaves_controller_unchanged() {
+        let mut ctrl = TrafficLightController::new();
+        let before = ctrl;
+        let plan = TimingPlan {
+            red_duration: 5000,
+            yellow_duration: 500,
+            green_duration: 0,
+        };
+        assert_eq!(
+            ctrl.apply_plan(plan),
+            Err(ConfigError::Duration(DurationError::TooShort(0)))
+        );
+        assert_eq!(ctrl, before);
+    }
+
+    #[test]
+    fn test_apply_plan_yellow_longer_than_green_rejected() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.mark_scheduled();
+        let before = ctrl;
+        let plan = TimingPlan {
+            red_duration: 5000,
+            yellow_duration: 3000,
+            green_duration: 2000,
+        };
+        assert_eq!(
+            ctrl.apply_plan(plan),
+            Err(ConfigError::Duration(DurationError::YellowNotShortest))
+        );
+        assert_eq!(ctrl, before);
+        assert!(!ctrl.needs_reschedule());
+    }
+
+    #[test]
+    fn test_apply_plan_too_long_rejected() {
+        let mut ctrl = TrafficLightController::new();
+        let plan = TimingPlan {
+            red_duration: MAX_DURATION_MS + 1,
+            ..TimingPlan::default()
+        };
+        assert!(ctrl.apply_plan(plan).is_err());
+        assert_eq!(ctrl.red_duration(), RED_DURATION_MS);
+    }
+
+    // ==================== TrafficLightController::tick() Tests ====================
+
+    #[test]
+    fn test_tick_partial_does_not_advance() {
+        let mut ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.tick(1000), None);
+        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+        assert_eq!(ctrl.elapsed_in_phase(), 1000);
+    }
+
+    #[test]
+    fn test_tick_full_duration_advances() {
+        let mut ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.tick(RED_DURATION_MS), Some(TrafficLightState::Green));
+        assert_eq!(ctrl.elapsed_in_phase(), 0);
+    }
+
+    #[test]
+    fn test_tick_exact_durations_full_cycle() {
+        let mut ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.tick(RED_DURATION_MS), Some(TrafficLightState::Green));
+        assert_eq!(
+            ctrl.tick(GREEN_DURATION_MS),
+            Some(TrafficLightState::Yellow)
+        );
+        assert_eq!(ctrl.tick(YELLOW_DURATION_MS), Some(TrafficLightState::Red));
+        assert_eq!(ctrl.elapsed_in_phase(), 0);
+        assert_eq!(ctrl.cycle_count(), 1);
+    }
+
+    #[test]
+    fn test_tick_partials_accumulate_and_carry() {
+        let mut ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.tick(1000), None);
+        assert_eq!(ctrl.tick(1999), None);
+        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+        assert_eq!(ctrl.tick(251), Some(TrafficLightState::Green));
+        assert_eq!(ctrl.elapsed_in_phase(), 250);
+    }
+
+    #[test]
+    fn test_tick_large_step_advances_multiple_phases() {
+        let mut ctrl = TrafficLightController::new();
+        let step = RED_DURATION_MS + GREEN_DURATION_MS + 400;
+        assert_eq!(ctrl.tick(step), Some(TrafficLightState::Yellow));
+        assert_eq!(ctrl.elapsed_in_phase(), 400);
+    }
+
+    #[test]
+    fn test_tick_full_cycle_step_returns_to_red() {
+        let mut ctrl = TrafficLightController::new();
+        let cycle = ctrl.total_cycle_duration();
+        assert_eq!(ctrl.tick(cycle), Some(TrafficLightState::Red));
+        assert_eq!(ctrl.elapsed_in_phase(), 0);
+        assert_eq!(ctrl.cycle_count(), 1);
+    }
+
+    #[test]
+    fn test_advance_resets_elapsed() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.tick(500);
+        ctrl.advance();
+        assert_eq!(ctrl.elapsed_in_phase(), 0);
+    }
+
+    // ==================== TrafficLightController::ms_since_cycle_start() Tests ====================
+
+    #[test]
+    fn test_ms_since_cycle_start_initial() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.ms_since_cycle_start(), 0);
+    }
+
+    #[test]
+    fn test_ms_since_cycle_start_within_red() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.tick(1200);
+        assert_eq!(ctrl.ms_since_cycle_start(), 1200);
+    }
+
+    #[test]
+    fn test_ms_since_cycle_start_within_green() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.tick(RED_DURATION_MS + 250);
+        assert_eq!(ctrl.ms_since_cycle_start(), RED_DURATION_MS + 250);
+    }
+
+    #[test]
+    fn test_ms_since_cycle_start_within_yellow() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.tick(RED_DURATION_MS + GREEN_DURATION_MS + 400);
+        assert!(ctrl.is_yellow());
+        assert_eq!(
+            ctrl.ms_since_cycle_start(),
+            RED_DURATION_MS + GREEN_DURATION_MS + 400
+        );
+    }
+
+    #[test]
+    fn test_ms_since_cycle_start_wraps_after_cycle() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.tick(RED_DURATION_MS + GREEN_DURATION_MS + YELLOW_DURATION_MS + 10);
+        assert!(ctrl.is_red());
+        assert_eq!(ctrl.ms_since_cycle_start(), 10);
+    }
+
+    // ==================== phase_difference_ms Function Tests ====================
+
+    #[test]
+    fn test_phase_difference_synchronized() {
+        let mut a = TrafficLightController::new();
+        let mut b = TrafficLightController::new();
+        a.tick(4200);
+        b.tick(4200);
+        assert_eq!(phase_difference_ms(&a, &b), 0);
+    }
+
+    #[test]
+    fn test_phase_difference_a_leads() {
+        let mut a = TrafficLightController::new();
+        let b = TrafficLightController::new();
+        a.tick(1500);
+        assert_eq!(phase_difference_ms(&a, &b), 1500);
+    }
+
+    #[test]
+    fn test_phase_difference_a_lags_wraps() {
+        let a = TrafficLightController::new();
+        let mut b = TrafficLightController::new();
+        b.tick(1500);
+        assert_eq!(phase_difference_ms(&a, &b), 7000 - 1500);
+    }
+
+    #[test]
+    fn test_phase_difference_across_phases() {
+        let mut a = TrafficLightController::new();
+        let mut b = TrafficLightController::new();
+        a.tick(RED_DURATION_MS + GREEN_DURATION_MS + 100);
+        b.tick(RED_DURATION_MS);
+        assert_eq!(phase_difference_ms(&a, &b), GREEN_DURATION_MS + 100);
+    }
+
+    // ==================== TrafficLightController::needs_reschedule() Tests ====================
+
+    #[test]
+    fn test_needs_reschedule_initially_false() {
+        let ctrl = TrafficLightController::new();
+        assert!(!ctrl.needs_reschedule());
+    }
+
+    #[test]
+    fn test_needs_reschedule_set_by_apply_plan() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.apply_plan(TimingPlan::default()).unwrap();
+        assert!(ctrl.needs_reschedule());
+    }
+
+    #[test]
+    fn test_needs_reschedule_not_set_by_rejected_plan() {
+        let mut ctrl = TrafficLightController::new();
+        let plan = TimingPlan {
+            red_duration: 0,
+            ..TimingPlan::default()
+        };
+        assert!(ctrl.apply_plan(plan).is_err());
+        assert!(!ctrl.needs_reschedule());
+    }
+
+    #[test]
+    fn test_mark_scheduled_clears_flag() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.apply_plan(TimingPlan::default()).unwrap();
+        ctrl.mark_scheduled();
+        assert!(!ctrl.needs_reschedule());
+    }
+
+    #[test]
+    fn test_needs_reschedule_not_set_by_advance() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        ctrl.tick(100);
+        assert!(!ctrl.needs_reschedule());
+    }
+
+    // ==================== TrafficLightController::phase_at_offset() Tests ====================
+
+    #[test]
+    fn test_phase_at_offset_start_is_red() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.phase_at_offset(0), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_phase_at_offset_boundaries() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.phase_at_offset(2999), TrafficLightState::Red);
+        assert_eq!(ctrl.phase_at_offset(3000), TrafficLightState::Green);
+        assert_eq!(ctrl.phase_at_offset(5999), TrafficLightState::Green);
+        assert_eq!(ctrl.phase_at_offset(6000), TrafficLightState::Yellow);
+        assert_eq!(ctrl.phase_at_offset(6999), TrafficLightState::Yellow);
+    }
+
+    #[test]
+    fn test_phase_at_offset_wraps() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.phase_at_offset(7000), TrafficLightState::Red);
+        assert_eq!(ctrl.phase_at_offset(7000 + 3500), TrafficLightState::Green);
+    }
+
+    // ==================== TrafficLightController::state_at() Tests ====================
+
+    #[test]
+    fn test_state_at_matches_phase_at_offset() {
+        let ctrl = TrafficLightController::new();
+        for offset in [0, 1500, 3000, 4500, 6000, 6500] {
+            assert_eq!(ctrl.state_at(0, offset), ctrl.phase_at_offset(offset));
+        }
+    }
+
+    #[test]
+    fn test_state_at_independent_of_cycles() {
+        let ctrl = TrafficLightController::new();
+        for cycles in [0, 1, 7, 1_000_000, u64::MAX] {
+            assert_eq!(ctrl.state_at(cycles, 4000), TrafficLightState::Green);
+        }
+    }
+
+    // ==================== TrafficLightController::durations_seconds() Tests ====================
+
+    #[test]
+    fn test_durations_seconds_default() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.durations_seconds(), (3, 1, 3));
+    }
+
+    #[test]
+    fn test_durations_seconds_truncates() {
+        let mut ctrl = TrafficLightController::new();
+        let plan = TimingPlan {
+            red_duration: 4999,
+            yellow_duration: 999,
+            green_duration: 2500,
+        };
+        ctrl.apply_plan(plan).unwrap();
+        assert_eq!(ctrl.durations_seconds(), (4, 0, 2));
+    }
+
+    // ==================== both_yellow Function Tests ====================
+
+    #[test]
+    fn test_both_yellow_detected() {
+        assert!(both_yellow(
+            TrafficLightState::Yellow,
+            TrafficLightState::Yellow
+        ));
+    }
+
+    #[test]
+    fn test_both_yellow_normal_states_pass() {
+        let states = [
+            TrafficLightState::Red,
+            TrafficLightState::Yellow,
+            TrafficLightState::Green,
+        ];
+        for a in states {
+            for b in states {
+                let expected = a == TrafficLightState::Yellow && b == TrafficLightState::Yellow;
+                assert_eq!(both_yellow(a, b), expected);
+            }
+        }
+    }
+
+    // ==================== corridor_utilization Function Tests ====================
+
+    #[test]
+    fn test_corridor_utilization_perfect_coordination() {
+        let ctrls = [TrafficLightController::new(); 3];
+        let offsets = [0, 1000, 2500];
+        let travel = [1000, 1500];
+        assert_eq!(corridor_utilization(&ctrls, &offsets, &travel), 100);
+    }
+
+    #[test]
+    fn test_corridor_utilization_misaligned_offset() {
+        let ctrls = [TrafficLightController::new(); 2];
+        let offsets = [0, 0];
+        let travel = [1000];
+        assert_eq!(corridor_utilization(&ctrls, &offsets, &travel), 67);
+    }
+
+    #[test]
+    fn test_corridor_utilization_fully_blocked() {
+        let ctrls = [TrafficLightController::new(); 2];
+        let offsets = [0, 3500];
+        let travel = [0];
+        assert_eq!(corridor_utilization(&ctrls, &offsets, &travel), 0);
+    }
+
+    #[test]
+    fn test_corridor_utilization_single_controller() {
+        let ctrls = [TrafficLightController::new()];
+        assert_eq!(corridor_utilization(&ctrls, &[0], &[]), 100);
+    }
+
+    #[test]
+    fn test_corridor_utilization_huge_travel() {
+        let ctrl = TrafficLightController::new();
+        let cycle = ctrl.total_cycle_duration();
+        let expected = if ctrl.phase_at_offset(u64::MAX % cycle + cycle) == TrafficLightState::Green
+        {
+            100
+        } else {
+            0
+        };
+        assert_eq!(
+            corridor_utilization(&[ctrl, ctrl], &[0, 0], &[u64::MAX]),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_corridor_utilization_empty() {
+        assert_eq!(corridor_utilization(&[], &[], &[]), 0);
+    }
+
+    // ==================== TrafficLightController::apply_jitter() Tests ====================
+
+    #[test]
+    fn test_apply_jitter_same_seed_same_result() {
+        let mut ctrl1 = TrafficLightController::new();
+        let mut ctrl2 = TrafficLightController::new();
+        ctrl1.apply_jitter(42, 250);
+        ctrl2.apply_jitter(42, 250);
+        assert_eq!(ctrl1, ctrl2);
+    }
+
+    #[test]
+    fn test_apply_jitter_different_seed_differs() {
+        let mut ctrl1 = TrafficLightController::new();
+        let mut ctrl2 = TrafficLightController::new();
+        ctrl1.apply_jitter(1, 250);
+        ctrl2.apply_jitter(2, 250);
+        assert_ne!(
+            (ctrl1.red_duration(), ctrl1.green_duration()),
+            (ctrl2.red_duration(), ctrl2.green_duration())
+        );
+    }
+
+    #[test]
+    fn test_apply_jitter_within_bound() {
+        for seed in 0..50 {
+            let mut ctrl = TrafficLightController::new();
+            ctrl.apply_jitter(seed, 200);
+            assert!(ctrl.red_duration().abs_diff(RED_DURATION_MS) <= 200);
+            assert!(ctrl.yellow_duration().abs_diff(YELLOW_DURATION_MS) <= 200);
+            assert!(ctrl.green_duration().abs_diff(GREEN_DURATION_MS) <= 200);
+        }
+    }
+
+    #[test]
+    fn test_apply_jitter_clamped_into_range() {
+        for seed in 0..50 {
+            let mut ctrl = TrafficLightController::new();
+            ctrl.apply_jitter(seed, u64::MAX);
+            for d in [
+                ctrl.red_duration(),
+                ctrl.yellow_duration(),
+                ctrl.green_duration(),
+            ] {
+                assert!((MIN_DURATION_MS..=MAX_DURATION_MS).contains(&d));
+            }
+        }
+    }
+
+    #[test]
+    fn test_apply_jitter_zero_is_identity() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.apply_jitter(7, 0);
+        assert_eq!(ctrl.red_duration(), RED_DURATION_MS);
+        assert_eq!(ctrl.yellow_duration(), YELLOW_DURATION_MS);
+        assert_eq!(ctrl.green_duration(), GREEN_DURATION_MS);
+    }
+
+    #[test]
+    fn test_apply_jitter_sets_reschedule() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.apply_jitter(3, 100);
+        assert!(ctrl.needs_reschedule());
+    }
+
+    // ==================== distinct_states Function Tests ====================
+
+    #[test]
+    fn test_distinct_states_empty() {
+        assert_eq!(distinct_states(&[]), 0);
+    }
+
+    #[test]
+    fn test_distinct_states_one() {
+        let log = [TrafficLightState::Red, TrafficLightState::Red];
+        assert_eq!(distinct_states(&log), 1);
+    }
+
+    #[test]
+    fn test_distinct_states_two() {
+        let log = [
+            TrafficLightState::Red,
+            TrafficLightState::Green,
+            TrafficLightState::Red,
+        ];
+        assert_eq!(distinct_states(&log), 2);
+    }
+
+    #[test]
+    fn test_distinct_states_three() {
+        let mut ctrl = TrafficLightController::new();
+        let log: Vec<TrafficLightState> = (0..6).map(|_| ctrl.advance()).collect();
+        assert_eq!(distinct_states(&log), 3);
+    }
+
+    #[test]
+    fn test_distinct_states_optional_phases() {
+        let log = [TrafficLightState::AllRed, TrafficLightState::RedYellow];
+        assert_eq!(distinct_states(&log), 2);
+    }
+
+    #[test]
+    fn test_distinct_states_all_five() {
+        let mut ctrl = TrafficLightControllerBuilder::new()
+            .red_yellow(true)
+            .all_red(true)
+            .build()
+            .unwrap();
+        let log: Vec<TrafficLightState> = (0..10).map(|_| ctrl.advance()).collect();
+        assert_eq!(distinct_states(&log), 5);
+    }
+
+    // ==================== TrafficLightController::lamp_duty_hours() Tests ====================
+
+    #[test]
+    fn test_lamp_duty_hours_default_ratio() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.lamp_duty_hours(700), [300, 100, 300]);
+    }
+
+    #[test]
+    fn test_lamp_duty_hours_sum_approximates_total() {
+        let ctrl = TrafficLightController::new();
+        let hours = ctrl.lamp_duty_hours(8760);
+        let total: u32 = hours.iter().sum();
+        assert!((8760 - 3..=8760).contains(&total));
+    }
+
+    #[test]
+    fn test_lamp_duty_hours_zero() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.lamp_duty_hours(0), [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_lamp_duty_hours_max_no_overflow() {
+        let ctrl = TrafficLightController::new();
+        let hours = ctrl.lamp_duty_hours(u32::MAX);
+        assert!(hours[0] > hours[1]);
+        assert_eq!(hours[0], hours[2]);
+    }
+
+    // ==================== TrafficLightController::previous() Tests ====================
+
+    #[test]
+    fn test_previous_from_red() {
+        let mut ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.previous(), TrafficLightState::Yellow);
+    }
+
+    #[test]
+    fn test_previous_from_yellow() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.previous();
+        assert_eq!(ctrl.previous(), TrafficLightState::Green);
+    }
+
+    #[test]
+    fn test_previous_from_green() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        assert_eq!(ctrl.previous(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_previous_returns_new_state() {
+        let mut ctrl = TrafficLightController::new();
+        let new_state = ctrl.previous();
+        assert_eq!(new_state, ctrl.current_state());
+    }
+
+    #[test]
+    fn test_advance_then_previous_round_trip() {
+        let mut ctrl = TrafficLightController::new();
+        for _ in 0..3 {
+            let original = ctrl.current_state();
+            ctrl.advance();
+            assert_eq!(ctrl.previous(), original);
+            ctrl.advance();
+        }
+    }
+
+    #[test]
+    fn test_previous_full_reverse_cycle() {
+        let mut ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.previous(), TrafficLightState::Yellow);
+        assert_eq!(ctrl.previous(), TrafficLightState::Green);
+        assert_eq!(ctrl.previous(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_previous_multiple_cycles() {
+        let mut ctrl = TrafficLightController::new();
+        for _ in 0..9 {
+            ctrl.previous();
+        }
+        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+    }
+
+    // ==================== timing_diff Function Tests ====================
+
+    #[test]
+    fn test_timing_diff_identical_is_empty() {
+        let ctrl = TrafficLightController::new();
+        assert!(timing_diff(&ctrl, &ctrl).is_empty());
+    }
+
+    #[test]
+    fn test_timing_diff_single_change() {
+        let old = TrafficLightController::new();
+        let mut new = TrafficLightController::new();
+        new.apply_plan(TimingPlan {
+            yellow_duration: 1500,
+            ..TimingPlan::default()
+        })
+        .unwrap();
+        assert_eq!(timing_diff(&old, &new).as_slice(), &[(1, 1500)]);
+    }
+
+    #[test]
+    fn test_timing_diff_all_changed() {
+        let old = TrafficLightController::new();
+        let mut new = TrafficLightController::new();
+        new.apply_plan(TimingPlan {
+            red_duration: 4000,
+            yellow_duration: 800,
+            green_duration: 5000,
+        })
+        .unwrap();
+        assert_eq!(
+            timing_diff(&old, &new).as_slice(),
+            &[(0, 4000), (1, 800), (2, 5000)]
+        );
+    }
+
+    #[test]
+    fn test_timing_diff_ignores_state() {
+        let old = TrafficLightController::new();
+        let mut new = TrafficLightController::new();
+        new.advance();
+        assert!(timing_diff(&old, &new).is_empty());
+    }
+
+    // ==================== TrafficLightController::apply_timing_diff() Tests ====================
+
+    #[test]
+    fn test_apply_timing_diff_round_trip() {
+        let mut ctrl = TrafficLightController::new();
+        let mut target = TrafficLightController::new();
+        target
+            .apply_plan(TimingPlan {
+                red_duration: 4500,
+                yellow_duration: 1000,
+                green_duration: 2500,
+            })
+            .unwrap();
+        let diff = timing_diff(&ctrl, &target);
+        assert_eq!(ctrl.apply_timing_diff(&diff), Ok(()));
+        assert_eq!(ctrl.timing_plan(), target.timing_plan());
+    }
+
+    #[test]
+    fn test_apply_timing_diff_empty_is_noop() {
+        let mut ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.apply_timing_diff(&[]), Ok(()));
+        assert_eq!(ctrl.timing_plan(), TimingPlan::default());
+    }
+
+    #[test]
+    fn test_apply_timing_diff_invalid_value_rolls_back() {
+        let mut ctrl = TrafficLightController::new();
+        let diff = [(0, 4000), (2, MAX_DURATION_MS + 1)];
+        assert_eq!(
+            ctrl.apply_timing_diff(&diff),
+            Err(ConfigError::Duration(DurationError::TooLong(
+                MAX_DURATION_MS + 1
+            )))
+        );
+        assert_eq!(ctrl.red_duration(), RED_DURATION_MS);
+        assert_eq!(ctrl.green_duration(), GREEN_DURATION_MS);
+    }
+
+    #[test]
+    fn test_apply_timing_diff_invalid_phase_rolls_back() {
+        let mut ctrl = TrafficLightController::new();
+        let diff = [(1, 500), (3, 1000)];
+        assert_eq!(
+            ctrl.apply_timing_diff(&diff),
+            Err(ConfigError::InvalidPhase(3))
+        );
+        assert_eq!(ctrl.yellow_duration(), YELLOW_DURATION_MS);
+    }
+
+    #[test]
+    fn test_timing_plan_matches_durations() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.timing_plan(), TimingPlan::default());
+    }
+
+    // ==================== TrafficLightController::with_durations() Tests ====================
+
+    #[test]
+    fn test_with_durations_in_range() {
+        let ctrl = TrafficLightController::with_durations(5000, 1500, 6000).unwrap();
+        assert_eq!(ctrl.red_duration(), 5000);
+        assert_eq!(ctrl.yellow_duration(), 1500);
+        assert_eq!(ctrl.green_duration(), 6000);
+    }
+
+    #[test]
+    fn test_with_durations_starts_at_red() {
+        let ctrl = TrafficLightController::with_durations(5000, 1500, 6000).unwrap();
+        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+        assert_eq!(ctrl.current_duration(), 5000);
+    }
+
+    #[test]
+    fn test_with_durations_bounds_inclusive() {
+        assert!(
+            TrafficLightController::with_durations(
+                MIN_DURATION_MS,
+                MIN_DURATION_MS,
+                MAX_DURATION_MS
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_with_durations_below_minimum() {
+        assert_eq!(
+            TrafficLightController::with_durations(3000, MIN_DURATION_MS - 1, 3000),
+            Err(DurationError::TooShort(MIN_DURATION_MS - 1))
+        );
+    }
+
+    #[test]
+    fn test_with_durations_above_maximum() {
+        assert_eq!(
+            TrafficLightController::with_durations(3000, 1000, MAX_DURATION_MS + 1),
+            Err(DurationError::TooLong(MAX_DURATION_MS + 1))
+        );
+    }
+
+    #[test]
+    fn test_with_durations_yellow_not_shortest() {
+        assert_eq!(
+            TrafficLightController::with_durations(5000, 3000, 2000),
+            Err(DurationError::YellowNotShortest)
+        );
+        assert_eq!(
+            TrafficLightController::with_durations(2000, 3000, 5000),
+            Err(DurationError::YellowNotShortest)
+        );
+    }
+
+    // ==================== TrafficLightController::green_for_demand() Tests ====================
+
+    #[test]
+    fn test_green_for_demand_formula() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.green_for_demand(600, 1800, 90), 2593);
+    }
+
+    #[test]
+    fn test_green_for_demand_grows_with_arrival() {
+        let ctrl = TrafficLightController::new();
+        let low = ctrl.green_for_demand(300, 1800, 90);
+        let high = ctrl.green_for_demand(700, 1800, 90);
+        assert!(high > low);
+    }
+
+    #[test]
+    fn test_green_for_demand_clamps_low() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.green_for_demand(0, 1800, 90), MIN_DURATION_MS);
+    }
+
+    #[test]
+    fn test_green_for_demand_clamps_high() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.green_for_demand(5000, 1800, 90), MAX_DURATION_MS);
+    }
+
+    #[test]
+    fn test_green_for_demand_zero_capacity() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.green_for_demand(600, 0, 90), MAX_DURATION_MS);
+        assert_eq!(ctrl.green_for_demand(600, 1800, 0), MAX_DURATION_MS);
+    }
+
+    // ==================== TrafficLightController::pedestrian_signal() Tests ====================
+
+    #[test]
+    fn test_pedestrian_signal_walk_on_red() {
+        let ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.pedestrian_signal(), PedestrianSignal::Walk);
+    }
+
+    #[test]
+    fn test_pedestrian_signal_dont_walk_on_green() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        assert_eq!(ctrl.pedestrian_signal(), PedestrianSignal::DontWalk);
+    }
+
+    #[test]
+    fn test_pedestrian_signal_dont_walk_on_yellow() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        ctrl.advance();
+        assert_eq!(ctrl.pedestrian_signal(), PedestrianSignal::DontWalk);
+    }
+
+    #[test]
+    fn test_pedestrian_signal_exclusive_with_traffic() {
+        let mut ctrl = TrafficLightController::new();
+        for _ in 0..6 {
+            let walk = ctrl.pedestrian_signal() == PedestrianSignal::Walk;
+            assert_eq!(walk, ctrl.is_red());
+            assert!(!(walk && ctrl.is_green()));
+            ctrl.advance();
+        }
+    }
+
+    #[test]
+    fn test_pedestrian_signal_inequality() {
+        assert_ne!(PedestrianSignal::Walk, PedestrianSignal::DontWalk);
+    }
+
+    // ==================== TrafficLightController::is_oversaturated() Tests ====================
+
+    #[test]
+    fn test_is_oversaturated_undersaturated() {
+        let ctrl = TrafficLightController::new();
+        assert!(!ctrl.is_oversaturated(600, 1800));
+    }
+
+    #[test]
+    fn test_is_oversaturated_oversaturated() {
+        let ctrl = TrafficLightController::new();
+        assert!(ctrl.is_oversaturated(900, 1800));
+    }
+
+    #[test]
+    fn test_is_oversaturated_at_capacity() {
+        let ctrl = TrafficLightController::with_durations(3000, 1000, 4000).unwrap();
+        assert!(!ctrl.is_oversaturated(900, 1800));
+        assert!(ctrl.is_oversaturated(901, 1800));
+    }
+
+    #[test]
+    fn test_is_oversaturated_longer_green_helps() {
+        let short = TrafficLightController::with_durations(3000, 1000, 2000).unwrap();
+        let long = TrafficLightController::with_durations(3000, 1000, 6000).unwrap();
+        assert!(short.is_oversaturated(700, 1800));
+        assert!(!long.is_oversaturated(700, 1800));
+    }
+
+    // ==================== All-Red Clearance Tests ====================
+
+    #[test]
+    fn test_all_red_disabled_by_default() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.advance();
+        ctrl.advance();
+        assert_eq!(ctrl.advance(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_all_red_full_sequence_enabled() {
+        let mut ctrl = TrafficLightController::new().with_all_red(true);
+        assert_eq!(ctrl.advance(), TrafficLightState::Green);
+        assert_eq!(ctrl.advance(), TrafficLightState::Yellow);
+        assert_eq!(ctrl.advance(), TrafficLightState::AllRed);
+        assert_eq!(ctrl.advance(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_all_red_full_sequence_disabled_explicitly() {
+        let mut ctrl = TrafficLightController::new().with_all_red(false);
+        assert_eq!(ctrl.advance(), TrafficLightState::Green);
+        assert_eq!(ctrl.advance(), TrafficLightState::Yellow);
+        assert_eq!(ctrl.advance(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_all_red_current_duration() {
+        let mut ctrl = TrafficLightController::new().with_all_red(true);
+        for _ in 0..3 {
+            ctrl.advance();
+        }
+        assert_eq!(ctrl.current_duration(), ALL_RED_DURATION_MS);
+        assert_eq!(ctrl.all_red_duration(), ALL_RED_DURATION_MS);
+    }
+
+    #[test]
+    fn test_all_red_lights_red_only() {
+        let mut ctrl = TrafficLightController::new().with_all_red(true);
+        for _ in 0..3 {
+            ctrl.advance();
+        }
+        assert!(ctrl.is_red() && !ctrl.is_yellow() && !ctrl.is_green());
+        assert_eq!(ctrl.pedestrian_signal(), PedestrianSignal::DontWalk);
+    }
+
+    #[test]
+    fn test_all_red_previous_reverses_sequence() {
+        let mut ctrl = TrafficLightController::new().with_all_red(true);
+        assert_eq!(ctrl.previous(), TrafficLightState::AllRed);
+        assert_eq!(ctrl.previous(), TrafficLightState::Yellow);
+        assert_eq!(ctrl.previous(), TrafficLightState::Green);
+        assert_eq!(ctrl.previous(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_all_red_tick_through_cycle() {
+        let mut ctrl = TrafficLightController::new().with_all_red(true);
+        ctrl.tick(RED_DURATION_MS + GREEN_DURATION_MS + YELLOW_DURATION_MS);
+        assert_eq!(ctrl.current_state(), TrafficLightState::AllRed);
+        assert_eq!(ctrl.tick(ALL_RED_DURATION_MS), Some(TrafficLightState::Red));
+    }
+
+    #[test]
+    fn test_all_red_phase_at_offset() {
+        let ctrl = TrafficLightController::new().with_all_red(true);
+        assert_eq!(ctrl.phase_at_offset(6999), TrafficLightState::Yellow);
+        assert_eq!(ctrl.phase_at_offset(7000), TrafficLightState::AllRed);
+        assert_eq!(ctrl.phase_at_offset(8000), TrafficLightState::Red);
+    }
+
+    // ==================== corridor_offsets Function Tests ====================
+
+    #[test]
+    fn test_corridor_offsets_cumulative() {
+        let mut out: heapless::Vec<u64, 4> = heapless::Vec::new();
+        corridor_offsets(&[1000, 1500, 2000], 7000, &mut out);
+        assert_eq!(out.as_slice(), &[0, 1000, 2500, 4500]);
+    }
+
+    #[test]
+    fn test_corridor_offsets_wrap_by_cycle() {
+        let mut out: heapless::Vec<u64, 4> = heapless::Vec::new();
+        corridor_offsets(&[4000, 4000, 4000], 7000, &mut out);
+        assert_eq!(out.as_slice(), &[0, 4000, 1000, 5000]);
+    }
+
+    #[test]
+    fn test_corridor_offsets_match_utilization() {
+        let mut out: heapless::Vec<u64, 3> = heapless::Vec::new();
+        let travel = [1200, 2300];
+        corridor_offsets(&travel, 7000, &mut out);
+        let ctrls = [TrafficLightController::new(); 3];
+        assert_eq!(corridor_utilization(&ctrls, &out, &travel), 100);
+    }
+
+    #[test]
+    fn test_corridor_offsets_truncates_at_capacity() {
+        let mut out: heapless::Vec<u64, 2> = heapless::Vec::new();
+        corridor_offsets(&[1000, 1000, 1000], 7000, &mut out);
+        assert_eq!(out.as_slice(), &[0, 1000]);
+    }
+
+    #[test]
+    fn test_corridor_offsets_clears_output() {
+        let mut out: heapless::Vec<u64, 4> = heapless::Vec::new();
+        out.push(99).unwrap();
+        corridor_offsets(&[], 7000, &mut out);
+        assert_eq!(out.as_slice(), &[0]);
+    }
+
+    #[test]
+    fn test_corridor_offsets_zero_cycle() {
+        let mut out: heapless::Vec<u64, 3> = heapless::Vec::new();
+        corridor_offsets(&[9000, 9000], 0, &mut out);
+        assert_eq!(out.as_slice(), &[0, 9000, 18000]);
+    }
+
+    // ==================== TrafficLightState Display Tests ====================
+
+    #[test]
+    fn test_state_display_red() {
+        assert_eq!(format!("{}", TrafficLightState::Red), "RED");
+    }
+
+    #[test]
+    fn test_state_display_yellow() {
+        assert_eq!(format!("{}", TrafficLightState::Yellow), "YELLOW");
+    }
+
+    #[test]
+    fn test_state_display_green() {
+        assert_eq!(format!("{}", TrafficLightState::Green), "GREEN");
+    }
+
+    #[test]
+    fn test_state_display_all_red() {
+        assert_eq!(format!("{}", TrafficLightState::AllRed), "ALL_RED");
+    }
+
+    #[test]
+    fn test_state_display_differs_from_debug() {
+        assert_ne!(
+            format!("{}", TrafficLightState::Red),
+            format!("{:?}", TrafficLightState::Red)
+        );
+    }
+
+    // ==================== tick_with_reference Tests ====================
+
+    #[test]
+    fn test_tick_with_reference_no_drift() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(controller.tick_with_reference(500, 500), 0);
+    }
+
+    #[test]
+    fn test_tick_with_reference_slow_clock() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(controller.tick_with_reference(500, 520), 20);
+    }
+
+    #[test]
+    fn test_tick_with_reference_fast_clock() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(controller.tick_with_reference(500, 480), -20);
+    }
+
+    #[test]
+    fn test_tick_with_reference_uses_observed_delta() {
+        let mut controller = TrafficLightController::new();
+        controller.tick_with_reference(2900, 3000);
+        assert_eq!(controller.current_state(), TrafficLightState::Green);
+        assert_eq!(controller.elapsed_in_phase(), 0);
+    }
+
+    #[test]
+    fn test_tick_with_reference_accumulates_observed() {
+        let mut controller = TrafficLightController::new();
+        controller.tick_with_reference(1000, 1200);
+        assert_eq!(controller.elapsed_in_phase(), 1200);
+    }
+
+    #[test]
+    fn test_tick_with_reference_saturates_drift() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(controller.tick_with_reference(u64::MAX, 0), i64::MIN);
+    }
+
+    // ==================== total_cycle_duration Tests ====================
+
+    #[test]
+    fn test_total_cycle_duration_default() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.total_cycle_duration(), 7000);
+    }
+
+    #[test]
+    fn test_total_cycle_duration_near_max() {
+        let controller = TrafficLightController::with_durations(
+            MAX_DURATION_MS,
+            MAX_DURATION_MS,
+            MAX_DURATION_MS,
+        )
+        .unwrap();
+        assert_eq!(controller.total_cycle_duration(), 3 * MAX_DURATION_MS);
+    }
+
+    #[test]
+    fn test_total_cycle_duration_includes_all_red() {
+        let controller = TrafficLightController::new().with_all_red(true);
+        assert_eq!(
+            controller.total_cycle_duration(),
+            7000 + ALL_RED_DURATION_MS
+        );
+    }
+
+    #[test]
+    fn test_total_cycle_duration_saturates_on_overflow() {
+        let mut controller = TrafficLightController::new();
+        controller.red_duration = u64::MAX;
+        controller.green_duration = 1;
+        assert_eq!(controller.total_cycle_duration(), u64::MAX);
+    }
+
+    // ==================== two_lamp_levels Tests ====================
+
+    #[test]
+    fn test_two_lamp_levels_red() {
+        assert_eq!(two_lamp_levels(TrafficLightState::Red), (true, false));
+    }
+
+    #[test]
+    fn test_two_lamp_levels_yellow_lights_stop() {
+        assert_eq!(two_lamp_levels(TrafficLightState::Yellow), (true, false));
+    }
+
+    #[test]
+    fn test_two_lamp_levels_green() {
+        assert_eq!(two_lamp_levels(TrafficLightState::Green), (false, true));
+    }
+
+    #[test]
+    fn test_two_lamp_levels_all_red() {
+        assert_eq!(two_lamp_levels(TrafficLightState::AllRed), (true, false));
+    }
+
+    #[test]
+    fn test_two_lamp_levels_exactly_one_lit() {
+        for state in [
+            TrafficLightState::Red,
+            TrafficLightState::Yellow,
+            TrafficLightState::Green,
+            TrafficLightState::AllRed,
+        ] {
+            let (stop, go) = two_lamp_levels(state);
+            assert!(stop ^ go);
+        }
+    }
+
+    // ==================== state_sequence Tests ====================
+
+    #[test]
+    fn test_state_sequence_from_red() {
+        let controller = TrafficLightController::with_durations(3000, 1000, 4000).unwrap();
+        let sequence: Vec<_> = controller.state_sequence().collect();
+        assert_eq!(
+            sequence,
+            [
+                (TrafficLightState::Red, 3000),
+                (TrafficLightState::Green, 4000),
+                (TrafficLightState::Yellow, 1000),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_state_sequence_yields_three_items() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.state_sequence().count(), 3);
+    }
+
+    #[test]
+    fn test_state_sequence_starts_at_current() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        let states: Vec<_> = controller.state_sequence().map(|(s, _)| s).collect();
+        assert_eq!(
+            states,
+            [
+                TrafficLightState::Green,
+                TrafficLightState::Yellow,
+                TrafficLightState::Red,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_state_sequence_does_not_modify_controller() {
+        let controller = TrafficLightController::new();
+        let _ = controller.state_sequence().count();
+        assert_eq!(controller.current_state(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_state_sequence_durations_sum_to_cycle() {
+        let controller = TrafficLightController::new().with_all_red(true);
+        let total: u64 = controller.state_sequence().map(|(_, d)| d).sum();
+        assert_eq!(total, controller.total_cycle_duration());
+    }
+
+    // ==================== reset Tests ====================
+
+    #[test]
+    fn test_reset_returns_to_red() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        controller.advance();
+        controller.reset();
+        assert_eq!(controller.current_state(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_reset_preserves_custom_durations() {
+        let mut controller = TrafficLightController::with_durations(5000, 2000, 4000).unwrap();
+        controller.advance();
+        controller.advance();
+        controller.advance();
+        controller.advance();
+        controller.reset();
+        assert_eq!(controller.current_state(), TrafficLightState::Red);
+        assert_eq!(controller.red_duration(), 5000);
+        assert_eq!(controller.yellow_duration(), 2000);
+        assert_eq!(controller.green_duration(), 4000);
+    }
+
+    #[test]
+    fn test_reset_clears_elapsed() {
+        let mut controller = TrafficLightController::new();
+        controller.tick(3500);
+        controller.reset();
+        assert_eq!(controller.elapsed_in_phase(), 0);
+    }
+
+    #[test]
+    fn test_reset_leaves_night_mode() {
+        let mut controller = TrafficLightController::new();
+        controller.set_night_mode(true);
+        controller.reset();
+        assert!(!controller.is_night_mode());
+        assert_eq!(controller.mode(), ControllerMode::Normal);
+        assert_eq!(controller.pin_levels(), (true, false, false));
+        assert!(controller.is_valid());
+        assert_eq!(controller.advance(), TrafficLightState::Green);
+    }
+
+    #[test]
+    fn test_reset_leaves_night_mode_dark_half() {
+        let mut controller = TrafficLightController::new();
+        controller.set_night_mode(true);
+        controller.advance();
+        controller.reset();
+        assert_eq!(controller.pin_levels(), (true, false, false));
+    }
+
+    #[test]
+    fn test_reset_leaves_preemption() {
+        let mut controller = TrafficLightController::new();
+        controller.preempt_green();
+        controller.reset();
+        assert!(!controller.is_preempted());
+        assert_eq!(controller.current_state(), TrafficLightState::Red);
+        assert_eq!(controller.advance(), TrafficLightState::Green);
+    }
+
+    #[test]
+    fn test_reset_cancels_flash_red() {
+        let mut controller = TrafficLightController::new();
+        controller.flash_red(4);
+        controller.advance();
+        controller.reset();
+        assert_eq!(controller.flashes_remaining(), 0);
+        assert!(!controller.is_fault());
+        assert_eq!(controller.pin_levels(), (true, false, false));
+    }
+
+    #[test]
+    fn test_reset_marks_dirty() {
+        let mut controller = TrafficLightController::new();
+        controller.mark_scheduled();
+        controller.reset();
+        assert!(controller.needs_reschedule());
+    }
+
+    // ==================== phases_remaining_in_cycle Tests ====================
+
+    #[test]
+    fn test_phases_remaining_from_red() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.phases_remaining_in_cycle(), 2);
+    }
+
+    #[test]
+    fn test_phases_remaining_from_green() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        assert_eq!(controller.phases_remaining_in_cycle(), 1);
+    }
+
+    #[test]
+    fn test_phases_remaining_from_yellow() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        controller.advance();
+        assert_eq!(controller.phases_remaining_in_cycle(), 0);
+    }
+
+    #[test]
+    fn test_phases_remaining_with_all_red() {
+        let mut controller = TrafficLightController::new().with_all_red(true);
+        assert_eq!(controller.phases_remaining_in_cycle(), 3);
+        controller.advance();
+        controller.advance();
+        assert_eq!(controller.phases_remaining_in_cycle(), 1);
+        controller.advance();
+        assert_eq!(controller.phases_remaining_in_cycle(), 0);
+    }
+
+    // ==================== Packed Word Tests ====================
+
+    #[test]
+    fn test_packed_word_new_is_zero() {
+        assert_eq!(TrafficLightController::new().to_packed_word(), 0);
+    }
+
+    #[test]
+    fn test_packed_word_layout() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        controller.tick(1234);
+        assert_eq!(controller.to_packed_word(), (1234 << 8) | 2);
+    }
+
+    #[test]
+    fn test_packed_word_round_trip_each_state() {
+        let mut controller = TrafficLightController::new().with_all_red(true);
+        for elapsed in [0, 1, 250, 999] {
+            for _ in 0..4 {
+                controller.advance();
+                controller.tick(elapsed);
+                let restored =
+                    TrafficLightController::from_packed_word(controller.to_packed_word());
+                assert_eq!(restored.current_state(), controller.current_state());
+                assert_eq!(restored.elapsed_in_phase(), elapsed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_packed_word_uses_default_durations() {
+        let restored = TrafficLightController::from_packed_word((500 << 8) | 1);
+        assert_eq!(restored.current_state(), TrafficLightState::Yellow);
+        assert_eq!(restored.red_duration(), RED_DURATION_MS);
+        assert_eq!(restored.yellow_duration(), YELLOW_DURATION_MS);
+        assert_eq!(restored.green_duration(), GREEN_DURATION_MS);
+    }
+
+    #[test]
+    fn test_packed_word_unknown_code_is_red() {
+        let restored = TrafficLightController::from_packed_word(0xFF);
+        assert_eq!(restored.current_state(), TrafficLightState::Red);
+    }
+
+    // ==================== Duration Setter Tests ====================
+
+    #[test]
+    fn test_set_red_duration_valid() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(controller.set_red_duration(4500), Ok(()));
+        assert_eq!(controller.current_duration(), 4500);
+    }
+
+    #[test]
+    fn test_set_red_duration_too_short() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(
+            controller.set_red_duration(MIN_DURATION_MS - 1),
+            Err(DurationError::TooShort(MIN_DURATION_MS - 1))
+        );
+        assert_eq!(controller.red_duration(), RED_DURATION_MS);
+    }
+
+    #[test]
+    fn test_set_red_duration_too_long() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(
+            controller.set_red_duration(MAX_DURATION_MS + 1),
+            Err(DurationError::TooLong(MAX_DURATION_MS + 1))
+        );
+        assert_eq!(controller.red_duration(), RED_DURATION_MS);
+    }
+
+    #[test]
+    fn test_set_yellow_duration_valid() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        controller.advance();
+        assert_eq!(controller.set_yellow_duration(1500), Ok(()));
+        assert_eq!(controller.current_duration(), 1500);
+    }
+
+    #[test]
+    fn test_set_yellow_duration_too_short() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(
+            controller.set_yellow_duration(0),
+            Err(DurationError::TooShort(0))
+        );
+        assert_eq!(controller.yellow_duration(), YELLOW_DURATION_MS);
+    }
+
+    #[test]
+    fn test_set_yellow_duration_too_long() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(
+            controller.set_yellow_duration(u64::MAX),
+            Err(DurationError::TooLong(u64::MAX))
+        );
+        assert_eq!(controller.yellow_duration(), YELLOW_DURATION_MS);
+    }
+
+    #[test]
+    fn test_set_green_duration_valid() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        assert_eq!(controller.set_green_duration(MAX_DURATION_MS), Ok(()));
+        assert_eq!(controller.current_duration(), MAX_DURATION_MS);
+    }
+
+    #[test]
+    fn test_set_green_duration_too_short() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(
+            controller.set_green_duration(MIN_DURATION_MS - 1),
+            Err(DurationError::TooShort(MIN_DURATION_MS - 1))
+        );
+        assert_eq!(controller.green_duration(), GREEN_DURATION_MS);
+    }
+
+    #[test]
+    fn test_set_green_duration_too_long() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(
+            controller.set_green_duration(MAX_DURATION_MS + 1),
+            Err(DurationError::TooLong(MAX_DURATION_MS + 1))
+        );
+        assert_eq!(controller.green_duration(), GREEN_DURATION_MS);
+    }
+
+    #[test]
+    fn test_set_duration_marks_dirty() {
+        let mut controller = TrafficLightController::new();
+        controller.set_green_duration(2000).unwrap();
+        assert!(controller.needs_reschedule());
+    }
+
+    #[test]
+    fn test_with_all_red_marks_dirty() {
+        let mut controller = TrafficLightController::new();
+        controller.mark_scheduled();
+        let controller = controller.with_all_red(true);
+        assert!(controller.needs_reschedule());
+    }
+
+    #[test]
+    fn test_with_red_yellow_marks_dirty() {
+        let mut controller = TrafficLightController::new();
+        controller.mark_scheduled();
+        let controller = controller.with_red_yellow(true);
+        assert!(controller.needs_reschedule());
+    }
+
+    #[test]
+    fn test_set_duration_rejected_leaves_clean() {
+        let mut controller = TrafficLightController::new();
+        let _ = controller.set_green_duration(0);
+        assert!(!controller.needs_reschedule());
+    }
+
+    // ==================== Fault Mode Tests ====================
+
+    #[test]
+    fn test_new_is_not_fault() {
+        let controller = TrafficLightController::new();
+        assert!(!controller.is_fault());
+        assert_eq!(controller.mode(), ControllerMode::Normal);
+    }
+
+    #[test]
+    fn test_enter_fault_mode_forces_red() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        controller.enter_fault_mode();
+        assert!(controller.is_fault());
+        assert_eq!(controller.mode(), ControllerMode::FlashingRed);
+        assert_eq!(controller.current_state(), TrafficLightState::Red);
+        assert!(controller.is_red());
+    }
+
+    #[test]
+    fn test_fault_mode_advance_toggles_red_lamp() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        assert_eq!(controller.advance(), TrafficLightState::Red);
+        assert!(!controller.is_red());
+        assert_eq!(controller.advance(), TrafficLightState::Red);
+        assert!(controller.is_red());
+    }
+
+    #[test]
+    fn test_fault_mode_never_lights_other_lamps() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        for _ in 0..6 {
+            controller.advance();
+            assert!(!controller.is_yellow());
+            assert!(!controller.is_green());
+            assert_eq!(controller.current_state(), TrafficLightState::Red);
+        }
+    }
+
+    #[test]
+    fn test_fault_mode_uses_flash_duration() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        assert_eq!(controller.current_duration(), FAULT_FLASH_MS);
+        controller.tick(FAULT_FLASH_MS);
+        assert!(!controller.is_red());
+    }
+
+    #[test]
+    fn test_fault_mode_pedestrians_wait() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        assert_eq!(controller.pedestrian_signal(), PedestrianSignal::DontWalk);
+    }
+
+    #[test]
+    fn test_clear_fault_mode_resumes_cycling() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        controller.advance();
+        controller.clear_fault_mode();
+        assert!(!controller.is_fault());
+        assert!(controller.is_red());
+        assert_eq!(controller.current_duration(), RED_DURATION_MS);
+        assert_eq!(controller.advance(), TrafficLightState::Green);
+        assert_eq!(controller.advance(), TrafficLightState::Yellow);
+    }
+
+    #[test]
+    fn test_fault_mode_marks_dirty() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        assert!(controller.needs_reschedule());
+        controller.mark_scheduled();
+        controller.clear_fault_mode();
+        assert!(controller.needs_reschedule());
+    }
+
+    // ==================== transition_times_of_day Tests ====================
+
+    #[test]
+    fn test_transition_times_default() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.transition_times_of_day(3600), [3600, 3603, 3606]);
+    }
+
+    #[test]
+    fn test_transition_times_custom_durations() {
+        let controller = TrafficLightController::with_durations(10000, 2000, 5000).unwrap();
+        assert_eq!(controller.transition_times_of_day(0), [0, 10, 15]);
+    }
+
+    #[test]
+    fn test_transition_times_wrap_at_midnight() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.transition_times_of_day(86_398), [86_398, 1, 4]);
+    }
+
+    #[test]
+    fn test_transition_times_start_past_midnight_wraps() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.transition_times_of_day(86_400), [0, 3, 6]);
+    }
+
+    // ==================== yellow_is_shortest Tests ====================
+
+    /// Sets durations one by one, allowing a non-shortest yellow.
+    fn with_setter_durations(red: u64, yellow: u64, green: u64) -> TrafficLightController {
+        let mut controller = TrafficLightController::new();
+        controller.set_red_duration(red).unwrap();
+        controller.set_yellow_duration(yellow).unwrap();
+        controller.set_green_duration(green).unwrap();
+        controller
+    }
+
+    #[test]
+    fn test_yellow_is_shortest_default() {
+        assert!(TrafficLightController::new().yellow_is_shortest());
+    }
+
+    #[test]
+    fn test_yellow_is_shortest_tie() {
+        let controller = TrafficLightController::with_durations(2000, 2000, 3000).unwrap();
+        assert!(controller.yellow_is_shortest());
+    }
+
+    #[test]
+    fn test_yellow_longer_than_green() {
+        let controller = with_setter_durations(3000, 2000, 1000);
+        assert!(!controller.yellow_is_shortest());
+    }
+
+    #[test]
+    fn test_yellow_longer_than_red() {
+        let controller = with_setter_durations(500, 1000, 3000);
+        assert!(!controller.yellow_is_shortest());
+    }
+
+    #[test]
+    fn test_yellow_is_shortest_ignores_all_red() {
+        let controller = TrafficLightController::new().with_all_red(true);
+        assert!(controller.yellow_duration() >= controller.all_red_duration());
+        assert!(controller.yellow_is_shortest());
+    }
+
+    // ==================== avg_power_mw Tests ====================
+
+    #[test]
+    fn test_avg_power_default_timing() {
+        let controller = TrafficLightController::new();
+        // (3000 * 700 + 1000 * 1400 + 3000 * 700) / 7000
+        assert_eq!(controller.avg_power_mw([700, 1400, 700]), 800);
+    }
+
+    #[test]
+    fn test_avg_power_equal_lamps() {
+        let controller = TrafficLightController::with_durations(5000, 1000, 2000).unwrap();
+        assert_eq!(controller.avg_power_mw([900, 900, 900]), 900);
+    }
+
+    #[test]
+    fn test_avg_power_only_green_draws() {
+        let controller = TrafficLightController::with_durations(3000, 1000, 4000).unwrap();
+        assert_eq!(controller.avg_power_mw([0, 0, 1600]), 800);
+    }
+
+    #[test]
+    fn test_avg_power_all_red_counts_as_red() {
+        let controller = TrafficLightController::new().with_all_red(true);
+        // (4000 * 1000) / 8000
+        assert_eq!(controller.avg_power_mw([1000, 0, 0]), 500);
+    }
+
+    // ==================== Lamp Fault Tests ====================
+
+    #[test]
+    fn test_faulted_lamp_none_by_default() {
+        assert_eq!(TrafficLightController::new().faulted_lamp(), None);
+    }
+
+    #[test]
+    fn test_report_lamp_fault_records_lamp() {
+        let mut controller = TrafficLightController::new();
+        controller.report_lamp_fault(2);
+        assert_eq!(controller.faulted_lamp(), Some(2));
+    }
+
+    #[test]
+    fn test_report_lamp_fault_enters_flashing_red() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        controller.report_lamp_fault(1);
+        assert!(controller.is_fault());
+        assert_eq!(controller.mode(), ControllerMode::FlashingRed);
+        assert_eq!(controller.current_state(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_clear_fault_mode_clears_faulted_lamp() {
+        let mut controller = TrafficLightController::new();
+        controller.report_lamp_fault(0);
+        controller.clear_fault_mode();
+        assert_eq!(controller.faulted_lamp(), None);
+        assert!(!controller.is_fault());
+    }
+
+    #[test]
+    fn test_enter_fault_mode_has_no_faulted_lamp() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        assert_eq!(controller.faulted_lamp(), None);
+    }
+
+    // ==================== current_lamp_off_in Tests ====================
+
+    #[test]
+    fn test_current_lamp_off_in_at_phase_start() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.current_lamp_off_in(), RED_DURATION_MS);
+    }
+
+    #[test]
+    fn test_current_lamp_off_in_after_tick() {
+        let mut controller = TrafficLightController::new();
+        controller.tick(1200);
+        assert_eq!(controller.current_lamp_off_in(), RED_DURATION_MS - 1200);
+    }
+
+    #[test]
+    fn test_current_lamp_off_in_matches_remaining_phase_time() {
+        let mut controller = TrafficLightController::new();
+        controller.tick(RED_DURATION_MS + 400);
+        assert_eq!(
+            controller.current_lamp_off_in(),
+            controller.current_duration() - controller.elapsed_in_phase()
+        );
+        assert_eq!(controller.current_lamp_off_in(), GREEN_DURATION_MS - 400);
+    }
+
+    #[test]
+    fn test_current_lamp_off_in_fault_mode() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        controller.tick(100);
+        assert_eq!(controller.current_lamp_off_in(), FAULT_FLASH_MS - 100);
+    }
+
+    // ==================== Serde Tests ====================
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_controller_round_trip() {
+        let mut controller = TrafficLightController::with_durations(4000, 1500, 5000)
+            .unwrap()
+            .with_all_red(true);
+        controller.advance();
+        controller.tick(250);
+        let json = serde_json::to_string(&controller).unwrap();
+        let parsed: TrafficLightController = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, controller);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_state_round_trip() {
+        for state in [
+            TrafficLightState::Red,
+            TrafficLightState::Yellow,
+            TrafficLightState::Green,
+            TrafficLightState::AllRed,
+        ] {
+            let json = serde_json::to_string(&state).unwrap();
+            assert_eq!(
+                serde_json::from_str::<TrafficLightState>(&json).unwrap(),
+                state
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_rejects_short_duration() {
+        let json = serde_json::to_string(&TrafficLightController::new())
+            .unwrap()
+            .replace("\"yellow_duration\":1000", "\"yellow_duration\":5");
+        assert!(serde_json::from_str::<TrafficLightController>(&json).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_rejects_long_duration() {
+        let json = serde_json::to_string(&TrafficLightController::new())
+            .unwrap()
+            .replace("\"green_duration\":3000", "\"green_duration\":10001");
+        assert!(serde_json::from_str::<TrafficLightController>(&json).is_err());
+    }
+
+    // ==================== cycle_score Tests ====================
+
+    #[test]
+    fn test_cycle_score_default_timing() {
+        let controller = TrafficLightController::new();
+        // throughput 1800 * 3000 / 7000 = 771, delay 4000^2 / 14000 = 1142
+        assert_eq!(controller.cycle_score(10, 1, 1800), 7710 - 1142);
+    }
+
+    #[test]
+    fn test_cycle_score_throughput_only() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.cycle_score(1, 0, 1800), 771);
+    }
+
+    #[test]
+    fn test_cycle_score_floors_at_zero() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.cycle_score(0, 1, 1800), 0);
+    }
+
+    #[test]
+    fn test_cycle_score_rises_with_throughput_weight() {
+        let controller = TrafficLightController::new();
+        assert!(controller.cycle_score(20, 1, 1800) > controller.cycle_score(10, 1, 1800));
+    }
+
+    #[test]
+    fn test_cycle_score_falls_with_delay_weight() {
+        let controller = TrafficLightController::new();
+        assert!(controller.cycle_score(10, 2, 1800) < controller.cycle_score(10, 1, 1800));
+    }
+
+    #[test]
+    fn test_cycle_score_rewards_longer_green() {
+        let short = TrafficLightController::new();
+        let long = TrafficLightController::with_durations(3000, 1000, 6000).unwrap();
+        assert!(long.cycle_score(10, 1, 1800) > short.cycle_score(10, 1, 1800));
+    }
+
+    // ==================== defmt Tests ====================
+
+    #[cfg(feature = "defmt")]
+    #[test]
+    fn test_defmt_format_implemented() {
+        fn assert_format<T: defmt::Format>() {}
+        assert_format::<TrafficLightState>();
+        assert_format::<ControllerMode>();
+        assert_format::<TrafficLightController>();
+    }
+
+    // ==================== simulate_cycles Tests ====================
+
+    #[test]
+    fn test_simulate_cycles_exact_resolution() {
+        let mut controller = TrafficLightController::new();
+        let stats = controller.simulate_cycles(5, 100);
+        assert_eq!(stats.red.samples, 5);
+        assert_eq!(stats.green.samples, 5);
+        assert_eq!(stats.yellow.samples, 5);
+        assert_eq!(stats.red.min_ms, RED_DURATION_MS);
+        assert_eq!(stats.red.max_ms, RED_DURATION_MS);
+        assert_eq!(stats.yellow.avg_ms, YELLOW_DURATION_MS);
+        assert_eq!(stats.green.avg_ms, GREEN_DURATION_MS);
+    }
+
+    #[test]
+    fn test_simulate_cycles_within_step_resolution() {
+        let step = 300;
+        let mut controller = TrafficLightController::with_durations(3100, 1000, 2500).unwrap();
+        let stats = controller.simulate_cycles(20, step);
+        for (stat, configured) in [(stats.red, 3100), (stats.yellow, 1000), (stats.green, 2500)] {
+            assert!(stat.samples > 0);
+            assert!(stat.min_ms + step > configured);
+            assert!(stat.max_ms < configured + step);
+            assert!(stat.min_ms <= stat.avg_ms && stat.avg_ms <= stat.max_ms);
+        }
+    }
+
+    #[test]
+    fn test_simulate_cycles_zero_cycles() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(controller.simulate_cycles(0, 100), PhaseStats::default());
+    }
+
+    #[test]
+    fn test_simulate_cycles_skips_partial_first_phase() {
+        let mut controller = TrafficLightController::new();
+        controller.tick(1000);
+        let stats = controller.simulate_cycles(1, 100);
+        assert_eq!(stats.red.samples, 0);
+        assert_eq!(stats.green.samples, 1);
+    }
+
+    // ==================== debounce_accepts Tests ====================
+
+    #[test]
+    fn test_debounce_accepts_held_press() {
+        assert!(debounce_accepts(1000, 1050, true, 50));
+    }
+
+    #[test]
+    fn test_debounce_rejects_within_window() {
+        assert!(!debounce_accepts(1000, 1049, true, 50));
+    }
+
+    #[test]
+    fn test_debounce_rejects_released_button() {
+        assert!(!debounce_accepts(1000, 1100, false, 50));
+    }
+
+    #[test]
+    fn test_debounce_zero_window() {
+        assert!(debounce_accepts(1000, 1000, true, 0));
+    }
+
+    #[test]
+    fn test_debounce_sample_before_edge_rejected() {
+        assert!(!debounce_accepts(1000, 900, true, 50));
+    }
+
+    #[test]
+    fn test_debounce_default_window() {
+        assert!(debounce_accepts(
+            0,
+            BUTTON_DEBOUNCE_MS,
+            true,
+            BUTTON_DEBOUNCE_MS
+        ));
+        assert!(!debounce_accepts(
+            0,
+            BUTTON_DEBOUNCE_MS - 1,
+            true,
+            BUTTON_DEBOUNCE_MS
+        ));
+    }
+
+    // ==================== cycle_count Tests ====================
+
+    #[test]
+    fn test_cycle_count_starts_at_zero() {
+        assert_eq!(TrafficLightController::new().cycle_count(), 0);
+    }
+
+    #[test]
+    fn test_cycle_count_increments_once_per_loop() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        controller.advance();
+        assert_eq!(controller.cycle_count(), 0);
+        controller.advance();
+        assert_eq!(controller.cycle_count(), 1);
+        for _ in 0..3 {
+            controller.advance();
+        }
+        assert_eq!(controller.cycle_count(), 2);
+    }
+
+    #[test]
+    fn test_cycle_count_with_all_red() {
+        let mut controller = TrafficLightController::new().with_all_red(true);
+        for _ in 0..4 {
+            controller.advance();
+        }
+        assert_eq!(controller.cycle_count(), 1);
+    }
+
+    #[test]
+    fn test_cycle_count_via_tick() {
+        let mut controller = TrafficLightController::new();
+        controller.tick(3 * controller.total_cycle_duration());
+        assert_eq!(controller.cycle_count(), 3);
+    }
+
+    #[test]
+    fn test_cycle_count_saturates() {
+        let mut controller = TrafficLightController::new();
+        controller.cycle_count = u32::MAX;
+        for _ in 0..3 {
+            controller.advance();
+        }
+        assert_eq!(controller.cycle_count(), u32::MAX);
+    }
+
+    #[test]
+    fn test_cycle_count_ignores_fault_flashing() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        for _ in 0..4 {
+            controller.advance();
+        }
+        assert_eq!(controller.cycle_count(), 0);
+    }
+
+    // ==================== pin_levels Tests ====================
+
+    #[test]
+    fn test_pin_levels_red() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.pin_levels(), (true, false, false));
+    }
+
+    #[test]
+    fn test_pin_levels_green() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        assert_eq!(controller.pin_levels(), (false, false, true));
+    }
+
+    #[test]
+    fn test_pin_levels_yellow() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        controller.advance();
+        assert_eq!(controller.pin_levels(), (false, true, false));
+    }
+
+    #[test]
+    fn test_pin_levels_all_red() {
+        let mut controller = TrafficLightController::new().with_all_red(true);
+        for _ in 0..3 {
+            controller.advance();
+        }
+        assert_eq!(controller.pin_levels(), (true, false, false));
+    }
+
+    #[test]
+    fn test_pin_levels_exactly_one_high() {
+        let mut controller = TrafficLightController::new().with_all_red(true);
+        for _ in 0..8 {
+            let (r, y, g) = controller.pin_levels();
+            assert_eq!(r as u8 + y as u8 + g as u8, 1);
+            controller.advance();
+        }
+    }
+
+    // ==================== Night Mode Tests ====================
+
+    #[test]
+    fn test_night_mode_off_by_default() {
+        assert!(!TrafficLightController::new().is_night_mode());
+    }
+
+    #[test]
+    fn test_set_night_mode_enters_flashing_yellow() {
+        let mut controller = TrafficLightController::new();
+        controller.set_night_mode(true);
+        assert!(controller.is_night_mode());
+        assert_eq!(controller.mode(), ControllerMode::FlashingYellow);
+        assert_eq!(controller.pin_levels(), (false, true, false));
+    }
+
+    #[test]
+    fn test_night_mode_advance_toggles_yellow() {
+        let mut controller = TrafficLightController::new();
+        controller.set_night_mode(true);
+        assert_eq!(controller.advance(), TrafficLightState::Yellow);
+        assert_eq!(controller.pin_levels(), (false, false, false));
+        controller.advance();
+        assert_eq!(controller.pin_levels(), (false, true, false));
+    }
+
+    #[test]
+    fn test_night_mode_uses_night_flash_duration() {
+        let mut controller = TrafficLightController::new();
+        controller.set_night_mode(true);
+        assert_eq!(controller.current_duration(), NIGHT_FLASH_MS);
+        assert_eq!(
+            controller.current_duration(),
+            controller.night_flash_duration()
+        );
+        controller.tick(NIGHT_FLASH_MS);
+        assert!(!controller.is_yellow());
+    }
+
+    #[test]
+    fn test_leave_night_mode_resumes_cycling() {
+        let mut controller = TrafficLightController::new();
+        controller.set_night_mode(true);
+        controller.advance();
+        controller.set_night_mode(false);
+        assert!(!controller.is_night_mode());
+        assert_eq!(controller.current_state(), TrafficLightState::Red);
+        assert_eq!(controller.advance(), TrafficLightState::Green);
+    }
+
+    #[test]
+    fn test_night_mode_ignored_during_fault() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        controller.set_night_mode(true);
+        assert!(controller.is_fault());
+        assert!(!controller.is_night_mode());
+    }
+
+    #[test]
+    fn test_fault_overrides_night_mode() {
+        let mut controller = TrafficLightController::new();
+        controller.set_night_mode(true);
+        controller.enter_fault_mode();
+        assert!(!controller.is_night_mode());
+        assert_eq!(controller.pin_levels(), (true, false, false));
+    }
+
+    #[test]
+    fn test_night_mode_no_phases_remaining() {
+        let mut controller = TrafficLightController::new();
+        controller.set_night_mode(true);
+        assert_eq!(controller.phases_remaining_in_cycle(), 0);
+    }
+
+    #[test]
+    fn test_night_mode_marks_dirty() {
+        let mut controller = TrafficLightController::new();
+        controller.set_night_mode(true);
+        assert!(controller.needs_reschedule());
+    }
+
+    // ==================== const new Tests ====================
+
+    #[test]
+    fn test_new_in_const_context() {
+        const CTRL: TrafficLightController = TrafficLightController::new();
+        const _: () = assert!(matches!(CTRL.current_state, TrafficLightState::Red));
+        assert_eq!(CTRL.current_state(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_new_in_static() {
+        static CTRL: TrafficLightController = TrafficLightController::new();
+        assert_eq!(CTRL, TrafficLightController::new());
+    }
+
+    // ==================== TrafficLightControllerBuilder Tests ====================
+
+    #[test]
+    fn test_builder_defaults() {
+        let controller = TrafficLightController::builder().build().unwrap();
+        assert_eq!(controller, TrafficLightController::new());
+    }
+
+    #[test]
+    fn test_builder_default_trait() {
+        assert_eq!(
+            TrafficLightControllerBuilder::default(),
+            TrafficLightControllerBuilder::new()
+        );
+    }
+
+    #[test]
+    fn test_builder_all_fields() {
+        let controller = TrafficLightControllerBuilder::new()
+            .red_duration(4000)
+            .yellow_duration(1500)
+            .green_duration(5000)
+            .start_state(TrafficLightState::Green)
+            .night_mode(false)
+            .build()
+            .unwrap();
+        assert_eq!(controller.red_duration(), 4000);
+        assert_eq!(controller.yellow_duration(), 1500);
+        assert_eq!(controller.green_duration(), 5000);
+        assert_eq!(controller.current_state(), TrafficLightState::Green);
+        assert!(!controller.is_night_mode());
+    }
+
+    #[test]
+    fn test_builder_partial_durations_fall_back() {
+        let controller = TrafficLightController::builder()
+            .green_duration(6000)
+            .build()
+            .unwrap();
+        assert_eq!(controller.red_duration(), RED_DURATION_MS);
+        assert_eq!(controller.yellow_duration(), YELLOW_DURATION_MS);
+        assert_eq!(controller.green_duration(), 6000);
+    }
+
+    #[test]
+    fn test_builder_night_mode() {
+        let controller = TrafficLightController::builder()
+            .night_mode(true)
+            .build()
+            .unwrap();
+        assert!(controller.is_night_mode());
+        assert!(!controller.needs_reschedule());
+    }
+
+    #[test]
+    fn test_builder_invalid_duration() {
+        let result = TrafficLightController::builder()
+            .red_duration(MIN_DURATION_MS - 1)
+            .build();
+        assert_eq!(result, Err(DurationError::TooShort(MIN_DURATION_MS - 1)));
+    }
+
+    #[test]
+    fn test_builder_rejects_long_yellow() {
+        let result = TrafficLightController::builder()
+            .yellow_duration(4000)
+            .build();
+        assert_eq!(result, Err(DurationError::YellowNotShortest));
+    }
+
+    // ==================== remaining_ms Tests ====================
+
+    #[test]
+    fn test_remaining_ms_under_duration() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.remaining_ms(1000), RED_DURATION_MS - 1000);
+        assert!(!controller.is_phase_complete(1000));
+    }
+
+    #[test]
+    fn test_remaining_ms_equal_to_duration() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.remaining_ms(RED_DURATION_MS), 0);
+        assert!(controller.is_phase_complete(RED_DURATION_MS));
+    }
+
+    #[test]
+    fn test_remaining_ms_over_duration_saturates() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.remaining_ms(RED_DURATION_MS + 500), 0);
+        assert_eq!(controller.remaining_ms(u64::MAX), 0);
+        assert!(controller.is_phase_complete(RED_DURATION_MS + 500));
+    }
+
+    #[test]
+    fn test_remaining_ms_follows_current_state() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        controller.advance();
+        assert_eq!(controller.remaining_ms(400), YELLOW_DURATION_MS - 400);
+    }
+
+    // ==================== starting_at Tests ====================
+
+    #[test]
+    fn test_starting_at_green() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::Green);
+        assert_eq!(controller.current_state(), TrafficLightState::Green);
+        assert_eq!(controller.current_duration(), GREEN_DURATION_MS);
+    }
+
+    #[test]
+    fn test_starting_at_red_matches_new() {
+        assert_eq!(
+            TrafficLightController::starting_at(TrafficLightState::Red),
+            TrafficLightController::new()
+        );
+    }
+
+    #[test]
+    fn test_starting_at_advances_from_each_state() {
+        for (start, next) in [
+            (TrafficLightState::Red, TrafficLightState::Green),
+            (TrafficLightState::Green, TrafficLightState::Yellow),
+            (TrafficLightState::Yellow, TrafficLightState::Red),
+        ] {
+            let mut controller = TrafficLightController::starting_at(start);
+            assert_eq!(controller.current_state(), start);
+            assert_eq!(controller.advance(), next);
+        }
+    }
+
+    #[test]
+    fn test_starting_at_uses_default_durations() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::Yellow);
+        assert_eq!(controller.red_duration(), RED_DURATION_MS);
+        assert_eq!(controller.yellow_duration(), YELLOW_DURATION_MS);
+        assert_eq!(controller.green_duration(), GREEN_DURATION_MS);
+    }
+
+    // ==================== peek_next Tests ====================
+
+    #[test]
+    fn test_peek_next_matches_advance() {
+        let mut controller = TrafficLightController::new();
+        for _ in 0..3 {
+            let peeked = controller.peek_next();
+            assert_eq!(controller.advance(), peeked);
+        }
+    }
+
+    #[test]
+    fn test_peek_next_does_not_mutate() {
+        let controller = TrafficLightController::new();
+        let before = controller;
+        assert_eq!(controller.peek_next(), TrafficLightState::Green);
+        assert_eq!(controller.current_state(), TrafficLightState::Red);
+        assert_eq!(controller, before);
+    }
+
+    #[test]
+    fn test_peek_next_from_each_state() {
+        for (start, next) in [
+            (TrafficLightState::Red, TrafficLightState::Green),
+            (TrafficLightState::Green, TrafficLightState::Yellow),
+            (TrafficLightState::Yellow, TrafficLightState::Red),
+        ] {
+            let controller = TrafficLightController::starting_at(start);
+            assert_eq!(controller.peek_next(), next);
+            assert_eq!(controller.current_state(), start);
+        }
+    }
+
+    #[test]
+    fn test_peek_next_with_all_red() {
+        let controller =
+            TrafficLightController::starting_at(TrafficLightState::Yellow).with_all_red(true);
+        assert_eq!(controller.peek_next(), TrafficLightState::AllRed);
+    }
+
+    // ==================== longest_phase / shortest_phase Tests ====================
+
+    #[test]
+    fn test_longest_phase_default_tie_goes_to_red() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.longest_phase(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_shortest_phase_default_is_yellow() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.shortest_phase(), TrafficLightState::Yellow);
+    }
+
+    #[test]
+    fn test_longest_phase_unique() {
+        let red = with_setter_durations(5000, 1000, 3000);
+        let yellow = with_setter_durations(3000, 6000, 4000);
+        let green = with_setter_durations(3000, 1000, 7000);
+        assert_eq!(red.longest_phase(), TrafficLightState::Red);
+        assert_eq!(yellow.longest_phase(), TrafficLightState::Yellow);
+        assert_eq!(green.longest_phase(), TrafficLightState::Green);
+    }
+
+    #[test]
+    fn test_shortest_phase_unique() {
+        let red = with_setter_durations(500, 1000, 3000);
+        let yellow = with_setter_durations(3000, 1000, 4000);
+        let green = with_setter_durations(3000, 1000, 700);
+        assert_eq!(red.shortest_phase(), TrafficLightState::Red);
+        assert_eq!(yellow.shortest_phase(), TrafficLightState::Yellow);
+        assert_eq!(green.shortest_phase(), TrafficLightState::Green);
+    }
+
+    #[test]
+    fn test_shortest_phase_tie_goes_to_red() {
+        let controller = TrafficLightController::with_durations(1000, 1000, 1000).unwrap();
+        assert_eq!(controller.shortest_phase(), TrafficLightState::Red);
+        assert_eq!(controller.longest_phase(), TrafficLightState::Red);
+    }
+
+    // ==================== Pin Index Tests ====================
+
+    #[test]
+    fn test_state_to_pin_index() {
+        assert_eq!(state_to_pin_index(TrafficLightState::Red), 0);
+        assert_eq!(state_to_pin_index(TrafficLightState::Yellow), 1);
+        assert_eq!(state_to_pin_index(TrafficLightState::Green), 2);
+    }
+
+    #[test]
+    fn test_state_to_pin_index_all_red_uses_red_pin() {
+        assert_eq!(state_to_pin_index(TrafficLightState::AllRed), 0);
+    }
+
+    #[test]
+    fn test_pin_index_to_state() {
+        assert_eq!(pin_index_to_state(0), Some(TrafficLightState::Red));
+        assert_eq!(pin_index_to_state(1), Some(TrafficLightState::Yellow));
+        assert_eq!(pin_index_to_state(2), Some(TrafficLightState::Green));
+    }
+
+    #[test]
+    fn test_pin_index_to_state_out_of_range() {
+        assert_eq!(pin_index_to_state(3), None);
+        assert_eq!(pin_index_to_state(usize::MAX), None);
+    }
+
+    #[test]
+    fn test_pin_index_round_trip() {
+        for idx in 0..3 {
+            assert_eq!(state_to_pin_index(pin_index_to_state(idx).unwrap()), idx);
+        }
+    }
+
+    // ==================== Intersection Tests ====================
+
+    #[test]
+    fn test_intersection_initial_states() {
+        let intersection = Intersection::new();
+        assert_eq!(
+            intersection.states(),
+            (TrafficLightState::Green, TrafficLightState::Red)
+        );
+        assert!(intersection.is_safe());
+    }
+
+    #[test]
+    fn test_intersection_default_matches_new() {
+        assert_eq!(Intersection::default(), Intersection::new());
+    }
+
+    #[test]
+    fn test_intersection_sequence() {
+        let mut intersection = Intersection::new();
+        assert_eq!(
+            intersection.advance(),
+            (TrafficLightState::Yellow, TrafficLightState::Red)
+        );
+        assert_eq!(
+            intersection.advance(),
+            (TrafficLightState::Red, TrafficLightState::Green)
+        );
+        assert_eq!(
+            intersection.advance(),
+            (TrafficLightState::Red, TrafficLightState::Yellow)
+        );
+        assert_eq!(
+            intersection.advance(),
+            (TrafficLightState::Green, TrafficLightState::Red)
+        );
+    }
+
+    #[test]
+    fn test_intersection_never_both_green() {
+        let mut intersection = Intersection::new();
+        for _ in 0..16 {
+            let (ns, ew) = intersection.advance();
+            assert!(!(ns == TrafficLightState::Green && ew == TrafficLightState::Green));
+        }
+    }
+
+    #[test]
+    fn test_intersection_never_both_yellow() {
+        let mut intersection = Intersection::new();
+        for _ in 0..16 {
+            let (ns, ew) = intersection.advance();
+            assert!(!both_yellow(ns, ew));
+            assert!(intersection.is_safe());
+        }
+    }
+
+    #[test]
+    fn test_intersection_one_direction_always_red() {
+        let mut intersection = Intersection::new();
+        for _ in 0..16 {
+            let (ns, ew) = intersection.advance();
+            assert!(ns == TrafficLightState::Red || ew == TrafficLightState::Red);
+        }
+    }
+
+    // ==================== is_valid Tests ====================
+
+    #[test]
+    fn test_is_valid_new() {
+        assert!(TrafficLightController::new().is_valid());
+    }
+
+    #[test]
+    fn test_is_valid_across_cycle() {
+        let mut controller = TrafficLightController::new().with_all_red(true);
+        for _ in 0..8 {
+            assert!(controller.is_valid());
+            controller.advance();
+        }
+    }
+
+    #[test]
+    fn test_is_valid_during_dark_flash() {
+        let mut controller = TrafficLightController::new();
+        controller.set_night_mode(true);
+        controller.advance();
+        assert_eq!(controller.pin_levels(), (false, false, false));
+        assert!(controller.is_valid());
+    }
+
+    #[test]
+    fn test_is_valid_rejects_out_of_range_duration() {
+        let mut controller = TrafficLightController::new();
+        controller.green_duration = MAX_DURATION_MS + 1;
+        assert!(!controller.is_valid());
+    }
+
+    #[test]
+    fn test_is_valid_rejects_zero_duration() {
+        let mut controller = TrafficLightController::new();
+        controller.yellow_duration = 0;
+        assert!(!controller.is_valid());
+    }
+
+    #[test]
+    fn test_is_valid_rejects_bad_clearance_duration() {
+        let mut controller = TrafficLightController::new().with_all_red(true);
+        controller.all_red_duration = 0;
+        assert!(!controller.is_valid());
+    }
+
+    // ==================== Hash Tests ====================
+
+    #[test]
+    fn test_state_as_map_key() {
+        let mut map: heapless::FnvIndexMap<TrafficLightState, u32, 4> =
+            heapless::FnvIndexMap::new();
+        map.insert(TrafficLightState::Red, 1).unwrap();
+        map.insert(TrafficLightState::Yellow, 2).unwrap();
+        map.insert(TrafficLightState::Green, 3).unwrap();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get(&TrafficLightState::Red), Some(&1));
+        assert_eq!(map.get(&TrafficLightState::Yellow), Some(&2));
+        assert_eq!(map.get(&TrafficLightState::Green), Some(&3));
+    }
+
+    #[test]
+    fn test_state_map_key_overwrites_equal() {
+        let mut map: heapless::FnvIndexMap<TrafficLightState, u32, 4> =
+            heapless::FnvIndexMap::new();
+        map.insert(TrafficLightState::Green, 1).unwrap();
+        map.insert(TrafficLightState::Green, 5).unwrap();
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get(&TrafficLightState::Green), Some(&5));
+    }
+
+    // ==================== phase_progress Tests ====================
+
+    #[test]
+    fn test_phase_progress_zero() {
+        assert_eq!(TrafficLightController::new().phase_progress(0), 0);
+    }
+
+    #[test]
+    fn test_phase_progress_half() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.phase_progress(RED_DURATION_MS / 2), 50);
+    }
+
+    #[test]
+    fn test_phase_progress_complete() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.phase_progress(RED_DURATION_MS), 100);
+    }
+
+    #[test]
+    fn test_phase_progress_overshoot_capped() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.phase_progress(RED_DURATION_MS * 3), 100);
+        assert_eq!(controller.phase_progress(u64::MAX), 100);
+    }
+
+    #[test]
+    fn test_phase_progress_zero_duration() {
+        let mut controller = TrafficLightController::new();
+        controller.red_duration = 0;
+        assert_eq!(controller.phase_progress(0), 100);
+    }
+
+    // ==================== state_to_rgb Tests ====================
+
+    #[test]
+    fn test_state_to_rgb_red() {
+        assert_eq!(state_to_rgb(TrafficLightState::Red), (255, 0, 0));
+    }
+
+    #[test]
+    fn test_state_to_rgb_yellow() {
+        assert_eq!(state_to_rgb(TrafficLightState::Yellow), (255, 191, 0));
+        assert_eq!(state_to_rgb(TrafficLightState::Yellow), YELLOW_RGB);
+    }
+
+    #[test]
+    fn test_state_to_rgb_green() {
+        assert_eq!(state_to_rgb(TrafficLightState::Green), (0, 255, 0));
+    }
+
+    #[test]
+    fn test_state_to_rgb_all_red() {
+        assert_eq!(state_to_rgb(TrafficLightState::AllRed), (255, 0, 0));
+    }
+
+    // ==================== Preemption Tests ====================
+
+    #[test]
+    fn test_preempt_green_forces_green() {
+        let mut controller = TrafficLightController::new();
+        controller.preempt_green();
+        assert!(controller.is_preempted());
+        assert_eq!(controller.current_state(), TrafficLightState::Green);
+        assert_eq!(controller.mode(), ControllerMode::Preempted);
+        assert_eq!(controller.pin_levels(), (false, false, true));
+        assert!(controller.is_valid());
+    }
+
+    #[test]
+    fn test_preempt_advance_is_noop() {
+        let mut controller = TrafficLightController::new();
+        controller.preempt_green();
+        for _ in 0..5 {
+            assert_eq!(controller.advance(), TrafficLightState::Green);
+        }
+        assert_eq!(controller.previous(), TrafficLightState::Green);
+        assert_eq!(controller.tick(100_000), None);
+        assert_eq!(controller.current_state(), TrafficLightState::Green);
+        assert_eq!(controller.cycle_count(), 0);
+    }
+
+    #[test]
+    fn test_preempt_elapsed_capped_at_green() {
+        let mut controller = TrafficLightController::new();
+        controller.preempt_green();
+        controller.tick(1000);
+        assert_eq!(controller.elapsed_in_phase(), 1000);
+        controller.tick(100_000);
+        assert_eq!(controller.elapsed_in_phase(), GREEN_DURATION_MS);
+        controller.tick(u64::MAX);
+        assert_eq!(controller.elapsed_in_phase(), GREEN_DURATION_MS);
+        assert_eq!(controller.time_in_state(TrafficLightState::Green), u64::MAX);
+    }
+
+    #[test]
+    fn test_clear_preempt_resumes_cycling() {
+        let mut controller = TrafficLightController::new();
+        controller.preempt_green();
+        controller.clear_preempt();
+        assert!(!controller.is_preempted());
+        assert_eq!(controller.mode(), ControllerMode::Normal);
+        assert_eq!(controller.current_state(), TrafficLightState::Green);
+        assert_eq!(controller.advance(), TrafficLightState::Yellow);
+        assert_eq!(controller.advance(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_clear_preempt_when_not_preempted_is_noop() {
+        let mut controller = TrafficLightController::new();
+        controller.mark_scheduled();
+        controller.clear_preempt();
+        assert_eq!(controller.current_state(), TrafficLightState::Red);
+        assert!(!controller.needs_reschedule());
+    }
+
+    #[test]
+    fn test_preempt_ignored_in_fault_mode() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        controller.preempt_green();
+        assert!(!controller.is_preempted());
+        assert!(controller.is_fault());
+    }
+
+    #[test]
+    fn test_preempt_overrides_night_mode() {
+        let mut controller = TrafficLightController::new();
+        controller.set_night_mode(true);
+        controller.preempt_green();
+        assert!(controller.is_preempted());
+        assert!(!controller.is_night_mode());
+        controller.set_night_mode(true);
+        assert!(controller.is_preempted());
+    }
+
+    #[test]
+    fn test_preempt_marks_dirty() {
+        let mut controller = TrafficLightController::new();
+        controller.mark_scheduled();
+        controller.preempt_green();
+        assert!(controller.needs_reschedule());
+        controller.mark_scheduled();
+        controller.clear_preempt();
+        assert!(controller.needs_reschedule());
+    }
+
+    // ==================== scale_durations Tests ====================
+
+    #[test]
+    fn test_scale_durations_down_10x() {
+        let mut controller = TrafficLightController::new();
+        assert!(controller.scale_durations(1, 10).is_ok());
+        assert_eq!(controller.red_duration(), 300);
+        assert_eq!(controller.yellow_duration(), 100);
+        assert_eq!(controller.green_duration(), 300);
+        assert_eq!(controller.all_red_duration(), 100);
+        assert_eq!(controller.night_flash_duration(), NIGHT_FLASH_MS);
+    }
+
+    #[test]
+    fn test_scale_durations_up() {
+        let mut controller = TrafficLightController::new();
+        assert!(controller.scale_durations(3, 2).is_ok());
+        assert_eq!(controller.red_duration(), 4500);
+        assert_eq!(controller.yellow_duration(), 1500);
+        assert_eq!(controller.green_duration(), 4500);
+    }
+
+    #[test]
+    fn test_scale_durations_past_max_rejected() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(
+            controller.scale_durations(4, 1),
+            Err(DurationError::TooLong(12000))
+        );
+        assert_eq!(controller.red_duration(), RED_DURATION_MS);
+        assert_eq!(controller.yellow_duration(), YELLOW_DURATION_MS);
+    }
+
+    #[test]
+    fn test_scale_durations_below_min_rejected() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(
+            controller.scale_durations(1, 20),
+            Err(DurationError::TooShort(50))
+        );
+        assert_eq!(controller.red_duration(), RED_DURATION_MS);
+    }
+
+    #[test]
+    fn test_scale_durations_zero_denominator() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(
+            controller.scale_durations(1, 0),
+            Err(DurationError::TooLong(u64::MAX))
+        );
+    }
+
+    #[test]
+    fn test_scale_durations_marks_dirty() {
+        let mut controller = TrafficLightController::new();
+        controller.mark_scheduled();
+        controller.scale_durations(1, 1).unwrap();
+        assert!(controller.needs_reschedule());
+    }
+
+    // ==================== u8 Conversion Tests ====================
+
+    #[test]
+    fn test_try_from_u8_valid() {
+        assert_eq!(TrafficLightState::try_from(0), Ok(TrafficLightState::Red));
+        assert_eq!(
+            TrafficLightState::try_from(1),
+            Ok(TrafficLightState::Yellow)
+        );
+        assert_eq!(TrafficLightState::try_from(2), Ok(TrafficLightState::Green));
+        assert_eq!(
+            TrafficLightState::try_from(3),
+            Ok(TrafficLightState::AllRed)
+        );
+    }
+
+    #[test]
+    fn test_try_from_u8_invalid() {
+        assert_eq!(TrafficLightState::try_from(5), Err(InvalidStateCode(5)));
+        assert_eq!(
+            TrafficLightState::try_from(0x7F),
+            Err(InvalidStateCode(0x7F))
+        );
+        assert_eq!(
+            TrafficLightState::try_from(u8::MAX),
+            Err(InvalidStateCode(u8::MAX))
+        );
+    }
+
+    #[test]
+    fn test_u8_from_state_round_trip() {
+        for state in [
+            TrafficLightState::Red,
+            TrafficLightState::Yellow,
+            TrafficLightState::Green,
+            TrafficLightState::AllRed,
+        ] {
+            assert_eq!(TrafficLightState::try_from(u8::from(state)), Ok(state));
+        }
+    }
+
+    #[test]
+    fn test_invalid_state_code_display() {
+        assert_eq!(format!("{}", InvalidStateCode(9)), "invalid state code 9");
+    }
+
+    // ==================== Status Byte Tests ====================
+
+    #[test]
+    fn test_pack_status_normal() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(controller.pack_status(), 0b0000_0000);
+        controller.advance();
+        assert_eq!(controller.pack_status(), 0b0000_0010);
+    }
+
+    #[test]
+    fn test_status_round_trip_normal_states() {
+        let mut controller = TrafficLightController::new().with_all_red(true);
+        for _ in 0..4 {
+            let (state, flags) = unpack_status(controller.pack_status()).unwrap();
+            assert_eq!(state, controller.current_state());
+            assert_eq!(flags, StatusFlags::default());
+            controller.advance();
+        }
+    }
+
+    #[test]
+    fn test_status_round_trip_fault() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        let byte = controller.pack_status();
+        assert_eq!(byte, 0b0000_0100);
+        let (state, flags) = unpack_status(byte).unwrap();
+        assert_eq!(state, TrafficLightState::Red);
+        assert!(flags.fault && !flags.night && !flags.preempt);
+    }
+
+    #[test]
+    fn test_status_round_trip_night() {
+        let mut controller = TrafficLightController::new();
+        controller.set_night_mode(true);
+        let byte = controller.pack_status();
+        assert_eq!(byte, 0b0000_1001);
+        let (state, flags) = unpack_status(byte).unwrap();
+        assert_eq!(state, TrafficLightState::Yellow);
+        assert!(!flags.fault && flags.night && !flags.preempt);
+    }
+
+    #[test]
+    fn test_status_round_trip_preempt() {
+        let mut controller = TrafficLightController::new();
+        controller.preempt_green();
+        let byte = controller.pack_status();
+        assert_eq!(byte, 0b0001_0010);
+        let (state, flags) = unpack_status(byte).unwrap();
+        assert_eq!(state, TrafficLightState::Green);
+        assert!(!flags.fault && !flags.night && flags.preempt);
+    }
+
+    #[test]
+    fn test_unpack_status_reserved_bits() {
+        assert_eq!(
+            unpack_status(0b0010_0000),
+            Err(StatusError::ReservedBits(0b0010_0000))
+        );
+        assert_eq!(
+            unpack_status(0b1100_0000),
+            Err(StatusError::ReservedBits(0b1100_0000))
+        );
+        assert_eq!(unpack_status(0xFF), Err(StatusError::ReservedBits(0xFF)));
+    }
+
+    #[test]
+    fn test_unpack_status_conflicting_flags() {
+        assert_eq!(
+            unpack_status(0b0000_1100),
+            Err(StatusError::ConflictingFlags(0b0000_1100))
+        );
+        assert_eq!(
+            unpack_status(0b0001_0110),
+            Err(StatusError::ConflictingFlags(0b0001_0110))
+        );
+    }
+
+    #[test]
+    fn test_status_layout_unchanged_without_red_yellow() {
+        let mut controller = TrafficLightController::new().with_all_red(true);
+        let mut bytes = [0u8; 4];
+        for byte in bytes.iter_mut() {
+            *byte = controller.pack_status();
+            controller.advance();
+        }
+        assert_eq!(bytes, [0b0000_0000, 0b0000_0010, 0b0000_0001, 0b0000_0011]);
+        for byte in 0..0b0010_0000u8 {
+            let result = unpack_status(byte);
+            if (byte >> 2).count_ones() > 1 {
+                assert_eq!(result, Err(StatusError::ConflictingFlags(byte)));
+            } else {
+                let (state, _) = result.unwrap();
+                assert_eq!(u8::from(state), byte & 0b11);
+            }
+        }
+    }
+
+    #[test]
+    fn test_status_error_display() {
+        assert_eq!(
+            format!("{}", StatusError::ReservedBits(0x80)),
+            "status byte 0x80 has reserved bits set"
+        );
+        assert_eq!(
+            format!("{}", StatusError::ConflictingFlags(0x0c)),
+            "status byte 0x0c has conflicting mode flags"
+        );
+    }
+
+    // ==================== bicolor_levels Tests ====================
+
+    #[test]
+    fn test_bicolor_levels_red() {
+        assert_eq!(bicolor_levels(TrafficLightState::Red), (true, false));
+    }
+
+    #[test]
+    fn test_bicolor_levels_green() {
+        assert_eq!(bicolor_levels(TrafficLightState::Green), (false, true));
+    }
+
+    #[test]
+    fn test_bicolor_levels_yellow_off() {
+        assert_eq!(bicolor_levels(TrafficLightState::Yellow), (false, false));
+        assert!(state_to_level(
+            TrafficLightState::Yellow,
+            TrafficLightState::Yellow
+        ));
+    }
+
+    #[test]
+    fn test_bicolor_levels_all_red() {
+        assert_eq!(bicolor_levels(TrafficLightState::AllRed), (true, false));
+    }
+
+    // ==================== StateCycle Tests ====================
+
+    #[test]
+    fn test_cycle_first_seven() {
+        let controller = TrafficLightController::new();
+        let mut states = [TrafficLightState::Red; 7];
+        for (slot, state) in states.iter_mut().zip(controller.cycle()) {
+            *slot = state;
+        }
+        assert_eq!(
+            states,
+            [
+                TrafficLightState::Red,
+                TrafficLightState::Green,
+                TrafficLightState::Yellow,
+                TrafficLightState::Red,
+                TrafficLightState::Green,
+                TrafficLightState::Yellow,
+                TrafficLightState::Red,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cycle_starts_at_current_state() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        let mut cycle = controller.cycle();
+        assert_eq!(cycle.next(), Some(TrafficLightState::Green));
+        assert_eq!(cycle.next(), Some(TrafficLightState::Yellow));
+        assert_eq!(cycle.next(), Some(TrafficLightState::Red));
+    }
+
+    #[test]
+    fn test_cycle_does_not_mutate_controller() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.cycle().take(100).count(), 100);
+        assert_eq!(controller.current_state(), TrafficLightState::Red);
+        assert_eq!(controller.cycle_count(), 0);
+    }
+
+    #[test]
+    fn test_cycle_with_all_red() {
+        let controller = TrafficLightController::new().with_all_red(true);
+        let mut cycle = controller.cycle().skip(2);
+        assert_eq!(cycle.next(), Some(TrafficLightState::Yellow));
+        assert_eq!(cycle.next(), Some(TrafficLightState::AllRed));
+        assert_eq!(cycle.next(), Some(TrafficLightState::Red));
+    }
+
+    // ==================== schedule Tests ====================
+
+    #[test]
+    fn test_schedule_first_entry() {
+        let controller = TrafficLightController::new();
+        let table = controller.schedule(1);
+        assert_eq!(table[0], (0, TrafficLightState::Red));
+    }
+
+    #[test]
+    fn test_schedule_timestamps_accumulate() {
+        let controller = TrafficLightController::new();
+        let table = controller.schedule(2);
+        assert_eq!(
+            table.as_slice(),
+            &[
+                (0, TrafficLightState::Red),
+                (3000, TrafficLightState::Green),
+                (6000, TrafficLightState::Yellow),
+                (7000, TrafficLightState::Red),
+                (10000, TrafficLightState::Green),
+                (13000, TrafficLightState::Yellow),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_schedule_entry_count() {
+        let controller = TrafficLightController::new();
+        for cycles in [0, 1, 5, 16] {
+            assert_eq!(controller.schedule(cycles).len(), cycles as usize * 3);
+        }
+    }
+
+    #[test]
+    fn test_schedule_truncates_at_capacity() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.schedule(100).len(), SCHEDULE_CAPACITY);
+    }
+
+    #[test]
+    fn test_schedule_starts_at_current_state() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        controller.tick(500);
+        let table = controller.schedule(1);
+        assert_eq!(table[0], (0, TrafficLightState::Green));
+        assert_eq!(table[1], (GREEN_DURATION_MS, TrafficLightState::Yellow));
+    }
+
+    #[test]
+    fn test_schedule_with_all_red() {
+        let controller = TrafficLightController::new().with_all_red(true);
+        let table = controller.schedule(1);
+        assert_eq!(table.len(), 4);
+        assert_eq!(table[3], (7000, TrafficLightState::AllRed));
+    }
+
+    // ==================== Intersection::conflict_detected() Tests ====================
+
+    #[test]
+    fn test_conflict_detected_initially_false() {
+        let intersection = Intersection::new();
+        assert!(!intersection.conflict_detected());
+    }
+
+    #[test]
+    fn test_conflict_never_detected_over_full_cycle() {
+        let mut intersection = Intersection::new();
+        for _ in 0..12 {
+            intersection.advance();
+            assert!(!intersection.conflict_detected());
+        }
+        assert_eq!(intersection.states(), Intersection::new().states());
+    }
+
+    #[test]
+    fn test_conflict_detected_both_green() {
+        let intersection = Intersection {
+            north_south: TrafficLightController::starting_at(TrafficLightState::Green),
+            east_west: TrafficLightController::starting_at(TrafficLightState::Green),
+        };
+        assert!(intersection.conflict_detected());
+        assert!(!intersection.is_safe());
+    }
+
+    // ==================== Minimum Green Tests ====================
+
+    #[test]
+    fn test_min_green_default() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.min_green_ms(), MIN_DURATION_MS);
+    }
+
+    #[test]
+    fn test_set_green_below_min_green_rejected() {
+        let mut controller = TrafficLightController::new();
+        controller.set_min_green(2000);
+        assert_eq!(
+            controller.set_green_duration(1500),
+            Err(DurationError::TooShort(1500))
+        );
+        assert_eq!(controller.green_duration(), GREEN_DURATION_MS);
+    }
+
+    #[test]
+    fn test_set_green_at_or_above_min_green_accepted() {
+        let mut controller = TrafficLightController::new();
+        controller.set_min_green(2000);
+        assert!(controller.set_green_duration(2000).is_ok());
+        assert!(controller.set_green_duration(2500).is_ok());
+        assert_eq!(controller.green_duration(), 2500);
+    }
+
+    #[test]
+    fn test_min_green_does_not_affect_other_phases() {
+        let mut controller = TrafficLightController::new();
+        controller.set_min_green(2000);
+        assert!(controller.set_yellow_duration(500).is_ok());
+    }
+
+    #[test]
+    fn test_set_min_green_clamped() {
+        let mut controller = TrafficLightController::new();
+        controller.set_min_green(0);
+        assert_eq!(controller.min_green_ms(), MIN_DURATION_MS);
+        controller.set_min_green(u64::MAX);
+        assert_eq!(controller.min_green_ms(), MAX_DURATION_MS);
+    }
+
+    // ==================== cmp_cycle Tests ====================
+
+    #[test]
+    fn test_cmp_cycle_equal() {
+        let a = TrafficLightController::new();
+        let b = TrafficLightController::with_durations(2000, 1000, 4000).unwrap();
+        assert_eq!(a.cmp_cycle(&b), core::cmp::Ordering::Equal);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_cmp_cycle_shorter() {
+        let a = TrafficLightController::with_durations(2000, 500, 2000).unwrap();
+        let b = TrafficLightController::new();
+        assert_eq!(a.cmp_cycle(&b), core::cmp::Ordering::Less);
+    }
+
+    #[test]
+    fn test_cmp_cycle_longer() {
+        let a = TrafficLightController::new().with_all_red(true);
+        let b = TrafficLightController::new();
+        assert_eq!(a.cmp_cycle(&b), core::cmp::Ordering::Greater);
+        assert_eq!(b.cmp_cycle(&a), core::cmp::Ordering::Less);
+    }
+
+    // ==================== duty_cycle Tests ====================
+
+    #[test]
+    fn test_duty_cycle_default_profile() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.duty_cycle(TrafficLightState::Red), 43);
+        assert_eq!(controller.duty_cycle(TrafficLightState::Yellow), 14);
+        assert_eq!(controller.duty_cycle(TrafficLightState::Green), 43);
+        assert_eq!(controller.duty_cycle(TrafficLightState::AllRed), 0);
+    }
+
+    #[test]
+    fn test_duty_cycle_default_sums_to_about_100() {
+        let controller = TrafficLightController::new();
+        let sum: u32 = [
+            TrafficLightState::Red,
+            TrafficLightState::Yellow,
+            TrafficLightState::Green,
+        ]
+        .into_iter()
+        .map(|state| controller.duty_cycle(state) as u32)
+        .sum();
+        assert!((99..=101).contains(&sum));
+    }
+
+    #[test]
+    fn test_duty_cycle_equal_durations() {
+        let controller = TrafficLightController::with_durations(2000, 2000, 2000).unwrap();
+        assert_eq!(controller.duty_cycle(TrafficLightState::Red), 33);
+        assert_eq!(controller.duty_cycle(TrafficLightState::Yellow), 33);
+        assert_eq!(controller.duty_cycle(TrafficLightState::Green), 33);
+    }
+
+    #[test]
+    fn test_duty_cycle_all_red_enabled() {
+        let controller = TrafficLightController::new().with_all_red(true);
+        assert_eq!(controller.duty_cycle(TrafficLightState::AllRed), 13);
+        assert_eq!(controller.duty_cycle(TrafficLightState::Red), 38);
+    }
 
-    // ==================== TrafficLightState Enum Tests ====================
+    // ==================== flash_red Tests ====================
 
     #[test]
-    fn test_state_red_exists() {
-        let _state = TrafficLightState::Red;
+    fn test_flash_red_enters_flashing_red() {
+        let mut controller = TrafficLightController::new();
+        controller.advance();
+        controller.flash_red(4);
+        assert!(controller.is_fault());
+        assert_eq!(controller.current_state(), TrafficLightState::Red);
+        assert!(controller.is_red());
+        assert_eq!(controller.flashes_remaining(), 4);
     }
 
     #[test]
-    fn test_state_yellow_exists() {
-        let _state = TrafficLightState::Yellow;
+    fn test_flash_red_exact_toggle_count() {
+        let mut controller = TrafficLightController::new();
+        controller.flash_red(6);
+        for toggle in 1..6 {
+            controller.advance();
+            assert!(controller.is_fault());
+            assert_eq!(controller.is_red(), toggle % 2 == 0);
+            assert_eq!(controller.flashes_remaining(), 6 - toggle);
+        }
+        controller.advance();
+        assert!(!controller.is_fault());
+        assert_eq!(controller.flashes_remaining(), 0);
     }
 
     #[test]
-    fn test_state_green_exists() {
-        let _state = TrafficLightState::Green;
+    fn test_flash_red_auto_resumes() {
+        let mut controller = TrafficLightController::new();
+        controller.flash_red(2);
+        controller.advance();
+        assert!(controller.is_fault());
+        assert!(!controller.is_red());
+        controller.advance();
+        assert!(!controller.is_fault());
+        assert_eq!(controller.mode(), ControllerMode::Normal);
+        assert_eq!(controller.current_state(), TrafficLightState::Red);
+        assert!(controller.is_red());
+        assert_eq!(controller.advance(), TrafficLightState::Green);
     }
 
     #[test]
-    fn test_state_equality_red() {
-        assert_eq!(TrafficLightState::Red, TrafficLightState::Red);
+    fn test_flash_red_zero_count_ignored() {
+        let mut controller = TrafficLightController::new();
+        controller.flash_red(0);
+        assert!(!controller.is_fault());
     }
 
     #[test]
-    fn test_state_equality_yellow() {
-        assert_eq!(TrafficLightState::Yellow, TrafficLightState::Yellow);
+    fn test_flash_red_does_not_clear_latched_fault() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        controller.flash_red(1);
+        controller.advance();
+        assert!(controller.is_fault());
     }
 
     #[test]
-    fn test_state_equality_green() {
-        assert_eq!(TrafficLightState::Green, TrafficLightState::Green);
+    fn test_fault_during_flash_red_latches() {
+        let mut controller = TrafficLightController::new();
+        controller.flash_red(2);
+        controller.enter_fault_mode();
+        for _ in 0..4 {
+            controller.advance();
+        }
+        assert!(controller.is_fault());
+        assert_eq!(controller.flashes_remaining(), 0);
     }
 
+    // ==================== TrafficLightState::from_name() Tests ====================
+
     #[test]
-    fn test_state_inequality_red_yellow() {
-        assert_ne!(TrafficLightState::Red, TrafficLightState::Yellow);
+    fn test_from_name_red() {
+        assert_eq!(
+            TrafficLightState::from_name("red"),
+            Ok(TrafficLightState::Red)
+        );
+        assert_eq!(
+            TrafficLightState::from_name("RED"),
+            Ok(TrafficLightState::Red)
+        );
+    }
+
+    #[test]
+    fn test_from_name_yellow_mixed_case() {
+        assert_eq!(
+            TrafficLightState::from_name("YeLLoW"),
+            Ok(TrafficLightState::Yellow)
+        );
+    }
+
+    #[test]
+    fn test_from_name_green_mixed_case() {
+        assert_eq!(
+            TrafficLightState::from_name("Green"),
+            Ok(TrafficLightState::Green)
+        );
+    }
+
+    #[test]
+    fn test_from_name_round_trips_display() {
+        for state in [
+            TrafficLightState::Red,
+            TrafficLightState::Yellow,
+            TrafficLightState::Green,
+            TrafficLightState::AllRed,
+        ] {
+            assert_eq!(
+                TrafficLightState::from_name(&format!("{}", state)),
+                Ok(state)
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_name_unrecognized() {
+        assert_eq!(TrafficLightState::from_name("blue"), Err(ParseStateError));
+        assert_eq!(TrafficLightState::from_name(""), Err(ParseStateError));
+        assert_eq!(TrafficLightState::from_name(" red"), Err(ParseStateError));
+    }
+
+    #[test]
+    fn test_parse_state_error_display() {
+        assert_eq!(
+            format!("{}", ParseStateError),
+            "unrecognized traffic light state name"
+        );
+    }
+
+    // ==================== lamp_levels Tests ====================
+
+    #[test]
+    fn test_lamp_levels_red() {
+        assert_eq!(lamp_levels(TrafficLightState::Red), (true, false, false));
+    }
+
+    #[test]
+    fn test_lamp_levels_yellow() {
+        assert_eq!(lamp_levels(TrafficLightState::Yellow), (false, true, false));
+    }
+
+    #[test]
+    fn test_lamp_levels_green() {
+        assert_eq!(lamp_levels(TrafficLightState::Green), (false, false, true));
+    }
+
+    #[test]
+    fn test_lamp_levels_all_red() {
+        assert_eq!(lamp_levels(TrafficLightState::AllRed), (true, false, false));
+    }
+
+    #[test]
+    fn test_lamp_levels_matches_controller_pin_levels() {
+        let mut controller = TrafficLightController::new().with_all_red(true);
+        for _ in 0..4 {
+            assert_eq!(
+                lamp_levels(controller.current_state()),
+                controller.pin_levels()
+            );
+            controller.advance();
+        }
+    }
+
+    // ==================== RedYellow Tests ====================
+
+    fn red_yellow_controller() -> TrafficLightController {
+        TrafficLightController::builder()
+            .red_yellow(true)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_red_yellow_disabled_by_default() {
+        let mut controller = TrafficLightController::builder().build().unwrap();
+        assert_eq!(controller.advance(), TrafficLightState::Green);
+    }
+
+    #[test]
+    fn test_builder_all_red_matches_with_all_red() {
+        let built = TrafficLightController::builder()
+            .all_red(true)
+            .build()
+            .unwrap();
+        let mut direct = TrafficLightController::new().with_all_red(true);
+        direct.mark_scheduled();
+        assert_eq!(built, direct);
+        assert!(
+            !TrafficLightController::builder()
+                .build()
+                .unwrap()
+                .all_red_enabled
+        );
+    }
+
+    #[test]
+    fn test_with_red_yellow_matches_builder() {
+        let mut controller = TrafficLightController::new().with_red_yellow(true);
+        controller.mark_scheduled();
+        assert_eq!(controller, red_yellow_controller());
+        assert_eq!(controller.advance(), TrafficLightState::RedYellow);
+        let mut controller = controller.with_red_yellow(false);
+        controller.reset();
+        assert_eq!(controller.advance(), TrafficLightState::Green);
+    }
+
+    #[test]
+    fn test_red_yellow_full_sequence() {
+        let mut controller = red_yellow_controller();
+        assert_eq!(controller.advance(), TrafficLightState::RedYellow);
+        assert_eq!(controller.advance(), TrafficLightState::Green);
+        assert_eq!(controller.advance(), TrafficLightState::Yellow);
+        assert_eq!(controller.advance(), TrafficLightState::Red);
+        assert_eq!(controller.cycle_count(), 1);
+    }
+
+    #[test]
+    fn test_red_yellow_lights_red_and_yellow() {
+        let mut controller = red_yellow_controller();
+        controller.advance();
+        assert!(controller.is_red());
+        assert!(controller.is_yellow());
+        assert!(!controller.is_green());
+        assert_eq!(controller.pin_levels(), (true, true, false));
+        assert!(controller.is_valid());
+        assert_eq!(controller.pedestrian_signal(), PedestrianSignal::DontWalk);
+    }
+
+    #[test]
+    fn test_red_yellow_previous() {
+        let mut controller = red_yellow_controller();
+        controller.advance();
+        controller.advance();
+        assert_eq!(controller.previous(), TrafficLightState::RedYellow);
+        assert_eq!(controller.previous(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_red_yellow_timing() {
+        let mut controller = red_yellow_controller();
+        assert_eq!(controller.total_cycle_duration(), 8000);
+        assert_eq!(
+            controller.phase_at_offset(RED_DURATION_MS),
+            TrafficLightState::RedYellow
+        );
+        assert_eq!(
+            controller.phase_at_offset(RED_DURATION_MS + RED_YELLOW_DURATION_MS),
+            TrafficLightState::Green
+        );
+        assert_eq!(
+            controller.tick(RED_DURATION_MS + RED_YELLOW_DURATION_MS),
+            Some(TrafficLightState::Green)
+        );
+        assert_eq!(controller.state_sequence().count(), 4);
+    }
+
+    #[test]
+    fn test_red_yellow_conversions() {
+        assert_eq!(u8::from(TrafficLightState::RedYellow), 4);
+        assert_eq!(
+            TrafficLightState::try_from(4),
+            Ok(TrafficLightState::RedYellow)
+        );
+        assert_eq!(
+            TrafficLightState::from_name("Red_Yellow"),
+            Ok(TrafficLightState::RedYellow)
+        );
+        assert_eq!(format!("{}", TrafficLightState::RedYellow), "RED_YELLOW");
+        assert_eq!(
+            lamp_levels(TrafficLightState::RedYellow),
+            (true, true, false)
+        );
+        assert_eq!(bicolor_levels(TrafficLightState::RedYellow), (true, false));
+        assert_eq!(state_to_rgb(TrafficLightState::RedYellow), (255, 95, 0));
+    }
+
+    #[test]
+    fn test_red_yellow_status_round_trip() {
+        let mut controller = red_yellow_controller();
+        controller.advance();
+        let byte = controller.pack_status();
+        assert_eq!(byte, 0b0100_0000);
+        assert_eq!(
+            unpack_status(byte),
+            Ok((TrafficLightState::RedYellow, StatusFlags::default()))
+        );
+        assert_eq!(
+            unpack_status(0b0100_0010),
+            Err(StatusError::InvalidState(0b0100_0010))
+        );
+    }
+
+    #[test]
+    fn test_red_yellow_lamp_duty() {
+        let controller = red_yellow_controller();
+        assert_eq!(controller.duty_cycle(TrafficLightState::RedYellow), 13);
+        assert_eq!(controller.lamp_duty_hours(8), [4, 2, 3]);
+    }
+
+    // ==================== time_in_state Tests ====================
+
+    #[test]
+    fn test_time_in_state_initially_zero() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.time_in_state(TrafficLightState::Red), 0);
+        assert_eq!(controller.time_in_state(TrafficLightState::Yellow), 0);
+        assert_eq!(controller.time_in_state(TrafficLightState::Green), 0);
+    }
+
+    #[test]
+    fn test_time_in_state_partial_ticks() {
+        let mut controller = TrafficLightController::new();
+        controller.tick(1000);
+        controller.tick(500);
+        assert_eq!(controller.time_in_state(TrafficLightState::Red), 1500);
+        assert_eq!(controller.time_in_state(TrafficLightState::Green), 0);
+    }
+
+    #[test]
+    fn test_time_in_state_across_phases() {
+        let mut controller = TrafficLightController::new();
+        for _ in 0..85 {
+            controller.tick(100);
+        }
+        assert_eq!(
+            controller.time_in_state(TrafficLightState::Red),
+            3000 + 1500
+        );
+        assert_eq!(controller.time_in_state(TrafficLightState::Green), 3000);
+        assert_eq!(controller.time_in_state(TrafficLightState::Yellow), 1000);
+    }
+
+    #[test]
+    fn test_time_in_state_large_tick_splits_phases() {
+        let mut controller = TrafficLightController::new();
+        controller.tick(2000);
+        controller.tick(5500);
+        assert_eq!(controller.time_in_state(TrafficLightState::Red), 3500);
+        assert_eq!(controller.time_in_state(TrafficLightState::Green), 3000);
+        assert_eq!(controller.time_in_state(TrafficLightState::Yellow), 1000);
+    }
+
+    #[test]
+    fn test_time_in_state_all_red_counts_as_red() {
+        let mut controller = TrafficLightController::new().with_all_red(true);
+        controller.tick(controller.total_cycle_duration());
+        assert_eq!(
+            controller.time_in_state(TrafficLightState::Red),
+            RED_DURATION_MS + ALL_RED_DURATION_MS
+        );
+        assert_eq!(
+            controller.time_in_state(TrafficLightState::AllRed),
+            controller.time_in_state(TrafficLightState::Red)
+        );
+    }
+
+    #[test]
+    fn test_time_in_state_preempted_counts_green() {
+        let mut controller = TrafficLightController::new();
+        controller.preempt_green();
+        controller.tick(20_000);
+        assert_eq!(controller.time_in_state(TrafficLightState::Green), 20_000);
+    }
+
+    #[test]
+    fn test_time_in_state_saturates() {
+        let mut controller = TrafficLightController::new();
+        controller.preempt_green();
+        controller.tick(u64::MAX);
+        controller.tick(u64::MAX);
+        assert_eq!(controller.time_in_state(TrafficLightState::Green), u64::MAX);
+    }
+
+    // ==================== TrafficLightState::complement() Tests ====================
+
+    #[test]
+    fn test_complement_green_is_red() {
+        assert_eq!(
+            TrafficLightState::Green.complement(),
+            TrafficLightState::Red
+        );
+    }
+
+    #[test]
+    fn test_complement_red_is_green() {
+        assert_eq!(
+            TrafficLightState::Red.complement(),
+            TrafficLightState::Green
+        );
+    }
+
+    #[test]
+    fn test_complement_yellow_is_red() {
+        assert_eq!(
+            TrafficLightState::Yellow.complement(),
+            TrafficLightState::Red
+        );
+    }
+
+    #[test]
+    fn test_complement_transitional_states_are_red() {
+        assert_eq!(
+            TrafficLightState::AllRed.complement(),
+            TrafficLightState::Red
+        );
+        assert_eq!(
+            TrafficLightState::RedYellow.complement(),
+            TrafficLightState::Red
+        );
+    }
+
+    #[test]
+    fn test_complement_never_both_green() {
+        for state in [
+            TrafficLightState::Red,
+            TrafficLightState::Yellow,
+            TrafficLightState::Green,
+            TrafficLightState::AllRed,
+            TrafficLightState::RedYellow,
+        ] {
+            assert!(
+                !(state == TrafficLightState::Green
+                    && state.complement() == TrafficLightState::Green)
+            );
+            assert!(
+                state == TrafficLightState::Red || state.complement() != TrafficLightState::Green
+            );
+        }
+    }
+
+    // ==================== advance_n Tests ====================
+
+    fn assert_advance_n_matches(controller: TrafficLightController, k: u64) {
+        let mut jumped = controller;
+        let mut stepped = controller;
+        jumped.advance_n(k);
+        for _ in 0..k {
+            stepped.advance();
+        }
+        assert_eq!(jumped, stepped, "k = {}", k);
+    }
+
+    #[test]
+    fn test_advance_n_zero() {
+        let mut controller = TrafficLightController::new();
+        controller.tick(500);
+        assert_eq!(controller.advance_n(0), TrafficLightState::Red);
+        assert_eq!(controller.elapsed_in_phase(), 500);
+    }
+
+    #[test]
+    fn test_advance_n_matches_advance() {
+        for k in [1, 2, 3, 4, 5, 7, 100, 1001, 12_345] {
+            assert_advance_n_matches(TrafficLightController::new(), k);
+            assert_advance_n_matches(
+                TrafficLightController::starting_at(TrafficLightState::Yellow),
+                k,
+            );
+        }
+    }
+
+    #[test]
+    fn test_advance_n_matches_with_optional_phases() {
+        let all_red = TrafficLightController::new().with_all_red(true);
+        let red_yellow = TrafficLightController::builder()
+            .red_yellow(true)
+            .all_red(true)
+            .build()
+            .unwrap();
+        for k in [1, 3, 4, 5, 9, 250] {
+            assert_advance_n_matches(all_red, k);
+            assert_advance_n_matches(red_yellow, k);
+        }
+    }
+
+    #[test]
+    fn test_advance_n_matches_in_flashing_modes() {
+        let mut fault = TrafficLightController::new();
+        fault.enter_fault_mode();
+        let mut night = TrafficLightController::new();
+        night.set_night_mode(true);
+        let mut self_test = TrafficLightController::new();
+        self_test.flash_red(5);
+        let mut preempted = TrafficLightController::new();
+        preempted.preempt_green();
+        for k in [1, 2, 4, 5, 6, 11] {
+            assert_advance_n_matches(fault, k);
+            assert_advance_n_matches(night, k);
+            assert_advance_n_matches(self_test, k);
+            assert_advance_n_matches(preempted, k);
+        }
+    }
+
+    #[test]
+    fn test_advance_n_large_counts_cycles() {
+        let mut controller = TrafficLightController::new();
+        assert_eq!(
+            controller.advance_n(3_000_000_001),
+            TrafficLightState::Green
+        );
+        assert_eq!(controller.cycle_count(), 1_000_000_000);
+    }
+
+    #[test]
+    fn test_advance_n_max_saturates_cycle_count() {
+        let mut controller = TrafficLightController::new();
+        let state = controller.advance_n(u64::MAX);
+        assert_eq!(state, TrafficLightState::Red);
+        assert_eq!(controller.cycle_count(), u32::MAX);
+    }
+
+    // ==================== green_wave_offset Tests ====================
+
+    #[test]
+    fn test_green_wave_offset_typical() {
+        assert_eq!(green_wave_offset(500, 10), 50_000);
+        assert_eq!(green_wave_offset(300, 15), 20_000);
+        assert_eq!(green_wave_offset(250, 14), 17_857);
+    }
+
+    #[test]
+    fn test_green_wave_offset_zero_distance() {
+        assert_eq!(green_wave_offset(0, 12), 0);
+    }
+
+    #[test]
+    fn test_green_wave_offset_zero_speed() {
+        assert_eq!(green_wave_offset(500, 0), 0);
+    }
+
+    #[test]
+    fn test_green_wave_offset_no_overflow() {
+        assert_eq!(green_wave_offset(u32::MAX, 1), u32::MAX as u64 * 1000);
+    }
+
+    #[test]
+    fn test_green_wave_offset_feeds_corridor_offsets() {
+        let travel = [green_wave_offset(500, 10), green_wave_offset(300, 10)];
+        let mut out: heapless::Vec<u64, 3> = heapless::Vec::new();
+        corridor_offsets(&travel, 60_000, &mut out);
+        assert_eq!(out.as_slice(), &[0, 50_000, 20_000]);
+    }
+
+    // ==================== Transition Classifier Tests ====================
+
+    #[test]
+    fn test_is_stopping_transition_distinct_pairs() {
+        use TrafficLightState::{Green, Red, Yellow};
+        assert!(is_stopping_transition(Green, Yellow));
+        assert!(is_stopping_transition(Yellow, Red));
+        assert!(!is_stopping_transition(Red, Green));
+        assert!(!is_stopping_transition(Red, Yellow));
+        assert!(!is_stopping_transition(Yellow, Green));
+        assert!(!is_stopping_transition(Green, Red));
+    }
+
+    #[test]
+    fn test_is_go_transition_distinct_pairs() {
+        use TrafficLightState::{Green, Red, Yellow};
+        assert!(is_go_transition(Red, Green));
+        assert!(!is_go_transition(Green, Yellow));
+        assert!(!is_go_transition(Yellow, Red));
+        assert!(!is_go_transition(Red, Yellow));
+        assert!(!is_go_transition(Yellow, Green));
+        assert!(!is_go_transition(Green, Red));
+    }
+
+    #[test]
+    fn test_transition_same_state_pairs() {
+        for state in [
+            TrafficLightState::Red,
+            TrafficLightState::Yellow,
+            TrafficLightState::Green,
+            TrafficLightState::AllRed,
+            TrafficLightState::RedYellow,
+        ] {
+            assert!(!is_stopping_transition(state, state));
+            assert!(!is_go_transition(state, state));
+        }
+    }
+
+    #[test]
+    fn test_transition_optional_phases() {
+        assert!(is_stopping_transition(
+            TrafficLightState::Yellow,
+            TrafficLightState::AllRed
+        ));
+        assert!(!is_stopping_transition(
+            TrafficLightState::AllRed,
+            TrafficLightState::Red
+        ));
+        assert!(is_go_transition(
+            TrafficLightState::RedYellow,
+            TrafficLightState::Green
+        ));
+        assert!(!is_go_transition(
+            TrafficLightState::Red,
+            TrafficLightState::RedYellow
+        ));
+    }
+
+    #[test]
+    fn test_transitions_over_one_cycle() {
+        let mut controller = TrafficLightController::new();
+        let (mut stops, mut goes) = (0, 0);
+        for _ in 0..3 {
+            let from = controller.current_state();
+            let to = controller.advance();
+            stops += is_stopping_transition(from, to) as u32;
+            goes += is_go_transition(from, to) as u32;
+        }
+        assert_eq!((stops, goes), (2, 1));
+    }
+
+    // ==================== Millis Setter Tests ====================
+
+    #[test]
+    fn test_setters_accept_millis() {
+        let mut controller = TrafficLightController::new();
+        assert!(controller.set_red_duration(Millis(4000)).is_ok());
+        assert!(controller.set_yellow_duration(Millis(500)).is_ok());
+        assert!(controller.set_green_duration(Millis(2000)).is_ok());
+        assert_eq!(controller.red_duration(), 4000);
+        assert_eq!(controller.yellow_duration(), 500);
+        assert_eq!(controller.green_duration(), 2000);
+    }
+
+    #[test]
+    fn test_setters_reject_saturated_millis() {
+        let mut controller = TrafficLightController::new();
+        let huge = Millis(MAX_DURATION_MS).saturating_mul(u64::MAX);
+        assert_eq!(
+            controller.set_red_duration(huge),
+            Err(DurationError::TooLong(u64::MAX))
+        );
+        assert!(controller.set_red_duration(huge.clamp_to_range()).is_ok());
+        assert_eq!(controller.red_duration(), MAX_DURATION_MS);
+    }
+
+    // ==================== TrafficLightState Default Tests ====================
+
+    #[test]
+    fn test_state_default_is_red() {
+        assert_eq!(TrafficLightState::default(), TrafficLightState::Red);
+    }
+
+    #[test]
+    fn test_state_default_matches_controller_start() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.current_state(), TrafficLightState::default());
+    }
+
+    #[test]
+    fn test_state_default_in_derived_struct() {
+        #[derive(Default)]
+        struct Holder {
+            state: TrafficLightState,
+        }
+        assert_eq!(Holder::default().state, TrafficLightState::Red);
+    }
+
+    // ==================== PhaseLockedPair Tests ====================
+
+    #[test]
+    fn test_phase_locked_pair_starts_together() {
+        let pair = PhaseLockedPair::new(
+            TrafficLightController::new(),
+            TrafficLightController::new(),
+            1500,
+        );
+        assert_eq!(
+            pair.states(),
+            (TrafficLightState::Red, TrafficLightState::Red)
+        );
+        assert_eq!(pair.offset_ms(), 1500);
+    }
+
+    #[test]
+    fn test_phase_locked_pair_follower_holds_during_offset() {
+        let mut pair = PhaseLockedPair::new(
+            TrafficLightController::new(),
+            TrafficLightController::new(),
+            1500,
+        );
+        pair.tick(RED_DURATION_MS);
+        assert_eq!(
+            pair.states(),
+            (TrafficLightState::Green, TrafficLightState::Red)
+        );
+        assert_eq!(pair.follower().elapsed_in_phase(), RED_DURATION_MS - 1500);
+    }
+
+    #[test]
+    fn test_phase_locked_pair_follower_lags_by_offset() {
+        let offset = 1500;
+        let mut pair = PhaseLockedPair::new(
+            TrafficLightController::new(),
+            TrafficLightController::new(),
+            offset,
+        );
+        let mut reference = TrafficLightController::new();
+        let mut history = std::vec![reference.current_state()];
+        let step = 100;
+        let cycle = reference.total_cycle_duration();
+        for _ in 0..(cycle * 2 / step) {
+            reference.tick(step);
+            history.push(reference.current_state());
+            pair.tick(step);
+            let now = history.len() - 1;
+            let lagged = now.saturating_sub((offset / step) as usize);
+            assert_eq!(pair.states(), (history[now], history[lagged]));
+        }
+    }
+
+    #[test]
+    fn test_phase_locked_pair_offset_consumed_across_ticks() {
+        let mut pair = PhaseLockedPair::new(
+            TrafficLightController::new(),
+            TrafficLightController::new(),
+            1500,
+        );
+        pair.tick(1000);
+        assert_eq!(pair.follower().elapsed_in_phase(), 0);
+        pair.tick(1000);
+        assert_eq!(pair.follower().elapsed_in_phase(), 500);
+        assert_eq!(pair.leader().elapsed_in_phase(), 2000);
+    }
+
+    #[test]
+    fn test_phase_locked_pair_zero_offset_in_lockstep() {
+        let mut pair = PhaseLockedPair::new(
+            TrafficLightController::new(),
+            TrafficLightController::new(),
+            0,
+        );
+        for _ in 0..20 {
+            let (leader, follower) = pair.tick(700);
+            assert_eq!(leader, follower);
+        }
+    }
+
+    // ==================== time_until Tests ====================
+
+    #[test]
+    fn test_time_until_already_in_target() {
+        for state in [
+            TrafficLightState::Red,
+            TrafficLightState::Yellow,
+            TrafficLightState::Green,
+        ] {
+            let controller = TrafficLightController::starting_at(state);
+            assert_eq!(controller.time_until(state, 500), 0);
+        }
     }
 
     #[test]
-    fn test_state_inequality_red_green() {
-        assert_ne!(TrafficLightState::Red, TrafficLightState::Green);
+    fn test_time_until_from_red() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::Red);
+        assert_eq!(controller.time_until(TrafficLightState::Green, 1000), 2000);
+        assert_eq!(controller.time_until(TrafficLightState::Yellow, 1000), 5000);
     }
 
     #[test]
-    fn test_state_inequality_yellow_green() {
-        assert_ne!(TrafficLightState::Yellow, TrafficLightState::Green);
+    fn test_time_until_from_green() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::Green);
+        assert_eq!(controller.time_until(TrafficLightState::Yellow, 0), 3000);
+        assert_eq!(controller.time_until(TrafficLightState::Red, 0), 4000);
     }
 
     #[test]
-    fn test_state_copy() {
-        let state = TrafficLightState::Red;
-        let copy = state;
-        assert_eq!(state, copy);
+    fn test_time_until_from_yellow() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::Yellow);
+        assert_eq!(controller.time_until(TrafficLightState::Red, 500), 500);
+        assert_eq!(controller.time_until(TrafficLightState::Green, 500), 3500);
     }
 
     #[test]
-    fn test_state_clone() {
-        let state = TrafficLightState::Green;
-        #[allow(clippy::clone_on_copy)]
-        let cloned = state.clone();
-        assert_eq!(state, cloned);
+    fn test_time_until_overshoot_saturates() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::Red);
+        assert_eq!(controller.time_until(TrafficLightState::Green, 9999), 0);
     }
 
     #[test]
-    fn test_state_debug_red() {
-        let debug_str = format!("{:?}", TrafficLightState::Red);
-        assert_eq!(debug_str, "Red");
+    fn test_time_until_with_all_red() {
+        let controller =
+            TrafficLightController::starting_at(TrafficLightState::Yellow).with_all_red(true);
+        assert_eq!(controller.time_until(TrafficLightState::AllRed, 0), 1000);
+        assert_eq!(controller.time_until(TrafficLightState::Red, 0), 2000);
+        assert_eq!(controller.time_until(TrafficLightState::Green, 0), 5000);
     }
 
     #[test]
-    fn test_state_debug_yellow() {
-        let debug_str = format!("{:?}", TrafficLightState::Yellow);
-        assert_eq!(debug_str, "Yellow");
+    fn test_time_until_with_red_yellow() {
+        let controller = TrafficLightControllerBuilder::new()
+            .red_yellow(true)
+            .build()
+            .unwrap();
+        assert_eq!(controller.time_until(TrafficLightState::RedYellow, 0), 3000);
+        assert_eq!(controller.time_until(TrafficLightState::Green, 0), 4000);
     }
 
     #[test]
-    fn test_state_debug_green() {
-        let debug_str = format!("{:?}", TrafficLightState::Green);
-        assert_eq!(debug_str, "Green");
+    fn test_time_until_unreachable_state() {
+        let controller = TrafficLightController::new();
+        assert_eq!(
+            controller.time_until(TrafficLightState::AllRed, 0),
+            u64::MAX
+        );
+        assert_eq!(
+            controller.time_until(TrafficLightState::RedYellow, 0),
+            u64::MAX
+        );
     }
 
     #[test]
-    fn test_state_size() {
-        assert_eq!(core::mem::size_of::<TrafficLightState>(), 1);
+    fn test_time_until_unreachable_while_flashing() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        assert_eq!(controller.time_until(TrafficLightState::Green, 0), u64::MAX);
     }
 
-    // ==================== state_to_level Function Tests ====================
+    // ==================== neopixel_frame Tests ====================
 
     #[test]
-    fn test_state_to_level_red_match() {
-        assert!(state_to_level(
-            TrafficLightState::Red,
-            TrafficLightState::Red
-        ));
+    fn test_neopixel_frame_red() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::Red);
+        assert_eq!(controller.neopixel_frame(), [0, 255, 0, 0, 0, 0, 0, 0, 0]);
     }
 
     #[test]
-    fn test_state_to_level_yellow_match() {
-        assert!(state_to_level(
-            TrafficLightState::Yellow,
-            TrafficLightState::Yellow
-        ));
+    fn test_neopixel_frame_yellow() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::Yellow);
+        let (r, g, b) = YELLOW_RGB;
+        assert_eq!(controller.neopixel_frame(), [0, 0, 0, g, r, b, 0, 0, 0]);
     }
 
     #[test]
-    fn test_state_to_level_green_match() {
-        assert!(state_to_level(
-            TrafficLightState::Green,
-            TrafficLightState::Green
-        ));
+    fn test_neopixel_frame_green() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::Green);
+        assert_eq!(controller.neopixel_frame(), [0, 0, 0, 0, 0, 0, 255, 0, 0]);
     }
 
     #[test]
-    fn test_state_to_level_red_no_match() {
-        assert!(!state_to_level(
-            TrafficLightState::Red,
-            TrafficLightState::Green
-        ));
+    fn test_neopixel_frame_all_red() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::AllRed);
+        assert_eq!(controller.neopixel_frame(), [0, 255, 0, 0, 0, 0, 0, 0, 0]);
     }
 
     #[test]
-    fn test_state_to_level_yellow_no_match() {
-        assert!(!state_to_level(
-            TrafficLightState::Yellow,
-            TrafficLightState::Red
-        ));
+    fn test_neopixel_frame_red_yellow_lights_two_pixels() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::RedYellow);
+        let (r, g, b) = YELLOW_RGB;
+        assert_eq!(controller.neopixel_frame(), [0, 255, 0, g, r, b, 0, 0, 0]);
     }
 
     #[test]
-    fn test_state_to_level_green_no_match() {
-        assert!(!state_to_level(
-            TrafficLightState::Green,
-            TrafficLightState::Yellow
-        ));
+    fn test_neopixel_frame_dark_half_of_flash() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        controller.advance();
+        assert_eq!(controller.neopixel_frame(), [0; 9]);
     }
 
-    // ==================== TrafficLightController::new() Tests ====================
-
-    #[test]
-    fn test_new_controller() {
-        let ctrl = TrafficLightController::new();
-        assert_eq!(ctrl.red_duration(), RED_DURATION_MS);
-    }
+    // ==================== Dimming Tests ====================
 
     #[test]
-    fn test_new_controller_starts_at_red() {
-        let ctrl = TrafficLightController::new();
-        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+    fn test_dim_level_default_full() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.dim_level(), 255);
+        assert_eq!(
+            controller.lamp_duty(TrafficLightState::Red),
+            crate::led::PWM_TOP
+        );
     }
 
     #[test]
-    fn test_new_controller_yellow_duration() {
-        let ctrl = TrafficLightController::new();
-        assert_eq!(ctrl.yellow_duration(), YELLOW_DURATION_MS);
+    fn test_lamp_duty_half_dim() {
+        let mut controller = TrafficLightController::starting_at(TrafficLightState::Green);
+        controller.set_dim_level(128);
+        assert_eq!(controller.dim_level(), 128);
+        assert_eq!(
+            controller.lamp_duty(TrafficLightState::Green),
+            led_level_to_duty(LedLevel::Dimmed(128))
+        );
+        assert_eq!(controller.lamp_duty(TrafficLightState::Green), 0x8080);
     }
 
     #[test]
-    fn test_new_controller_green_duration() {
-        let ctrl = TrafficLightController::new();
-        assert_eq!(ctrl.green_duration(), GREEN_DURATION_MS);
+    fn test_lamp_duty_inactive_lamps_off() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::Green);
+        assert_eq!(controller.lamp_duty(TrafficLightState::Red), 0);
+        assert_eq!(controller.lamp_duty(TrafficLightState::Yellow), 0);
     }
 
     #[test]
-    fn test_new_returns_consistent_value() {
-        let ctrl1 = TrafficLightController::new();
-        let ctrl2 = TrafficLightController::new();
-        assert_eq!(ctrl1, ctrl2);
+    fn test_lamp_duty_zero_level_dark() {
+        let mut controller = TrafficLightController::new();
+        controller.set_dim_level(0);
+        assert_eq!(controller.lamp_duty(TrafficLightState::Red), 0);
     }
 
-    // ==================== TrafficLightController::default() Tests ====================
-
     #[test]
-    fn test_default_equals_new() {
-        let default = TrafficLightController::default();
-        let new = TrafficLightController::new();
-        assert_eq!(default, new);
+    fn test_lamp_duty_red_yellow_lights_both() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::RedYellow);
+        assert_eq!(
+            controller.lamp_duty(TrafficLightState::Red),
+            crate::led::PWM_TOP
+        );
+        assert_eq!(
+            controller.lamp_duty(TrafficLightState::Yellow),
+            crate::led::PWM_TOP
+        );
+        assert_eq!(controller.lamp_duty(TrafficLightState::Green), 0);
     }
 
     #[test]
-    fn test_default_starts_at_red() {
-        let default = TrafficLightController::default();
-        assert_eq!(default.current_state(), TrafficLightState::Red);
+    fn test_lamp_duty_dark_half_of_flash() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        controller.advance();
+        assert_eq!(controller.lamp_duty(TrafficLightState::Red), 0);
     }
 
-    // ==================== TrafficLightController::advance() Tests ====================
+    // ==================== Transition Table Tests ====================
 
     #[test]
-    fn test_advance_from_red() {
-        let mut ctrl = TrafficLightController::new();
-        assert_eq!(ctrl.advance(), TrafficLightState::Green);
+    fn test_transitions_match_advance() {
+        for (from, to) in TRANSITIONS {
+            let mut controller = TrafficLightController::starting_at(from);
+            assert_eq!(controller.advance(), to);
+            assert_eq!(next_state(from), to);
+        }
     }
 
     #[test]
-    fn test_advance_from_green() {
-        let mut ctrl = TrafficLightController::new();
-        ctrl.advance();
-        assert_eq!(ctrl.advance(), TrafficLightState::Yellow);
+    fn test_next_state_matches_advance_for_all_states() {
+        for state in [
+            TrafficLightState::Red,
+            TrafficLightState::Yellow,
+            TrafficLightState::Green,
+            TrafficLightState::AllRed,
+            TrafficLightState::RedYellow,
+        ] {
+            let mut controller = TrafficLightController::starting_at(state);
+            assert_eq!(controller.advance(), next_state(state));
+        }
     }
 
     #[test]
-    fn test_advance_from_yellow() {
-        let mut ctrl = TrafficLightController::new();
-        ctrl.advance();
-        ctrl.advance();
-        assert_eq!(ctrl.advance(), TrafficLightState::Red);
+    fn test_next_state_optional_phases() {
+        assert_eq!(
+            next_state(TrafficLightState::AllRed),
+            TrafficLightState::Red
+        );
+        assert_eq!(
+            next_state(TrafficLightState::RedYellow),
+            TrafficLightState::Green
+        );
     }
 
     #[test]
-    fn test_advance_returns_new_state() {
-        let mut ctrl = TrafficLightController::new();
-        let new_state = ctrl.advance();
-        assert_eq!(new_state, ctrl.current_state());
+    fn test_transitions_no_duplicate_sources() {
+        for (i, (from, _)) in TRANSITIONS.iter().enumerate() {
+            assert!(TRANSITIONS[i + 1..].iter().all(|(other, _)| other != from));
+        }
     }
 
     #[test]
-    fn test_advance_full_cycle() {
-        let mut ctrl = TrafficLightController::new();
-        ctrl.advance();
-        ctrl.advance();
-        ctrl.advance();
-        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+    fn test_transitions_every_state_reachable() {
+        for (from, _) in TRANSITIONS {
+            assert!(TRANSITIONS.iter().any(|(_, to)| *to == from));
+        }
     }
 
     #[test]
-    fn test_advance_multiple_cycles() {
-        let mut ctrl = TrafficLightController::new();
-        for _ in 0..9 {
-            ctrl.advance();
+    fn test_transitions_form_single_cycle() {
+        let mut state = TrafficLightState::Red;
+        for _ in 0..TRANSITIONS.len() {
+            state = next_state(state);
         }
-        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+        assert_eq!(state, TrafficLightState::Red);
+        assert_eq!(next_state(TrafficLightState::Red), TrafficLightState::Green);
     }
 
-    // ==================== TrafficLightController::current_duration() Tests ====================
+    // ==================== Flashing Don't Walk Tests ====================
 
     #[test]
-    fn test_current_duration_red() {
-        let ctrl = TrafficLightController::new();
-        assert_eq!(ctrl.current_duration(), RED_DURATION_MS);
+    fn test_flashing_walk_disabled_by_default() {
+        let mut ctrl = TrafficLightController::new();
+        assert_eq!(ctrl.flashing_walk_ms(), 0);
+        ctrl.tick(RED_DURATION_MS - 1);
+        assert_eq!(ctrl.pedestrian_signal(), PedestrianSignal::Walk);
     }
 
     #[test]
-    fn test_current_duration_green() {
+    fn test_flashing_walk_early_in_red() {
         let mut ctrl = TrafficLightController::new();
-        ctrl.advance();
-        assert_eq!(ctrl.current_duration(), GREEN_DURATION_MS);
+        ctrl.set_flashing_walk_ms(1000);
+        ctrl.tick(500);
+        assert_eq!(ctrl.pedestrian_signal(), PedestrianSignal::Walk);
     }
 
     #[test]
-    fn test_current_duration_yellow() {
+    fn test_flashing_walk_late_in_red() {
         let mut ctrl = TrafficLightController::new();
-        ctrl.advance();
-        ctrl.advance();
-        assert_eq!(ctrl.current_duration(), YELLOW_DURATION_MS);
+        ctrl.set_flashing_walk_ms(1000);
+        ctrl.tick(RED_DURATION_MS - 1000);
+        assert_eq!(ctrl.pedestrian_signal(), PedestrianSignal::FlashingDontWalk);
+        ctrl.tick(999);
+        assert_eq!(ctrl.pedestrian_signal(), PedestrianSignal::FlashingDontWalk);
     }
 
-    // ==================== TrafficLightController::is_red() Tests ====================
+    #[test]
+    fn test_flashing_walk_dont_walk_on_green_and_yellow() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.set_flashing_walk_ms(1000);
+        ctrl.tick(RED_DURATION_MS);
+        assert!(ctrl.is_green());
+        assert_eq!(ctrl.pedestrian_signal(), PedestrianSignal::DontWalk);
+        ctrl.tick(GREEN_DURATION_MS);
+        assert!(ctrl.is_yellow());
+        ctrl.tick(YELLOW_DURATION_MS - 1);
+        assert_eq!(ctrl.pedestrian_signal(), PedestrianSignal::DontWalk);
+    }
 
     #[test]
-    fn test_is_red_initial() {
-        let ctrl = TrafficLightController::new();
-        assert!(ctrl.is_red());
+    fn test_flashing_walk_longer_than_red() {
+        let mut ctrl = TrafficLightController::new();
+        ctrl.set_flashing_walk_ms(RED_DURATION_MS * 2);
+        assert_eq!(ctrl.pedestrian_signal(), PedestrianSignal::FlashingDontWalk);
     }
 
     #[test]
-    fn test_is_red_after_advance() {
+    fn test_flashing_walk_fault_waits() {
         let mut ctrl = TrafficLightController::new();
-        ctrl.advance();
-        assert!(!ctrl.is_red());
+        ctrl.set_flashing_walk_ms(1000);
+        ctrl.enter_fault_mode();
+        assert_eq!(ctrl.pedestrian_signal(), PedestrianSignal::DontWalk);
     }
 
-    // ==================== TrafficLightController::is_yellow() Tests ====================
+    // ==================== average_with Tests ====================
 
     #[test]
-    fn test_is_yellow_initial() {
-        let ctrl = TrafficLightController::new();
-        assert!(!ctrl.is_yellow());
+    fn test_average_with_arithmetic_means() {
+        let a = TrafficLightController::with_durations(2000, 1000, 4000).unwrap();
+        let b = TrafficLightController::with_durations(6000, 2000, 8000).unwrap();
+        let avg = a.average_with(&b).unwrap();
+        assert_eq!(avg.red_duration(), 4000);
+        assert_eq!(avg.yellow_duration(), 1500);
+        assert_eq!(avg.green_duration(), 6000);
+        assert_eq!(avg.all_red_duration(), ALL_RED_DURATION_MS);
     }
 
     #[test]
-    fn test_is_yellow_after_two_advances() {
-        let mut ctrl = TrafficLightController::new();
-        ctrl.advance();
-        ctrl.advance();
-        assert!(ctrl.is_yellow());
+    fn test_average_with_rounds_down() {
+        let a = TrafficLightController::with_durations(3001, 1000, 3000).unwrap();
+        let b = TrafficLightController::with_durations(3000, 1000, 3000).unwrap();
+        assert_eq!(a.average_with(&b).unwrap().red_duration(), 3000);
     }
 
-    // ==================== TrafficLightController::is_green() Tests ====================
+    #[test]
+    fn test_average_with_is_symmetric() {
+        let a = TrafficLightController::with_durations(2000, 1000, 4000).unwrap();
+        let b = TrafficLightController::with_durations(6000, 2000, 8000).unwrap();
+        assert_eq!(a.average_with(&b), b.average_with(&a));
+    }
 
     #[test]
-    fn test_is_green_initial() {
-        let ctrl = TrafficLightController::new();
-        assert!(!ctrl.is_green());
+    fn test_average_with_starts_at_red() {
+        let mut a = TrafficLightController::new();
+        a.advance();
+        a.tick(500);
+        let avg = a.average_with(&TrafficLightController::new()).unwrap();
+        assert_eq!(avg.current_state(), TrafficLightState::Red);
+        assert_eq!(avg.elapsed_in_phase(), 0);
     }
 
     #[test]
-    fn test_is_green_after_advance() {
-        let mut ctrl = TrafficLightController::new();
-        ctrl.advance();
-        assert!(ctrl.is_green());
+    fn test_average_with_keeps_options() {
+        let a = TrafficLightController::new().with_all_red(true);
+        let avg = a.average_with(&TrafficLightController::new()).unwrap();
+        assert_eq!(avg.total_cycle_duration(), a.total_cycle_duration());
     }
 
-    // ==================== State Exclusivity Tests ====================
+    // ==================== max_red_wait Tests ====================
 
     #[test]
-    fn test_only_one_state_active_red() {
-        let ctrl = TrafficLightController::new();
-        assert!(ctrl.is_red() && !ctrl.is_yellow() && !ctrl.is_green());
+    fn test_max_red_wait_clearance_disabled() {
+        let controller = TrafficLightController::new();
+        assert_eq!(controller.max_red_wait(), RED_DURATION_MS);
     }
 
     #[test]
-    fn test_only_one_state_active_green() {
-        let mut ctrl = TrafficLightController::new();
-        ctrl.advance();
-        assert!(!ctrl.is_red() && !ctrl.is_yellow() && ctrl.is_green());
+    fn test_max_red_wait_clearance_enabled() {
+        let controller = TrafficLightController::new().with_all_red(true);
+        assert_eq!(
+            controller.max_red_wait(),
+            RED_DURATION_MS + ALL_RED_DURATION_MS
+        );
     }
 
     #[test]
-    fn test_only_one_state_active_yellow() {
-        let mut ctrl = TrafficLightController::new();
-        ctrl.advance();
-        ctrl.advance();
-        assert!(!ctrl.is_red() && ctrl.is_yellow() && !ctrl.is_green());
+    fn test_max_red_wait_with_red_yellow() {
+        let controller = TrafficLightControllerBuilder::new()
+            .red_yellow(true)
+            .all_red(true)
+            .build()
+            .unwrap();
+        assert_eq!(
+            controller.max_red_wait(),
+            RED_DURATION_MS + ALL_RED_DURATION_MS + RED_YELLOW_DURATION_MS
+        );
     }
 
-    // ==================== Trait Implementation Tests ====================
-
     #[test]
-    fn test_controller_clone() {
-        let ctrl1 = TrafficLightController::new();
-        let ctrl2 = ctrl1;
-        assert_eq!(ctrl1.red_duration(), ctrl2.red_duration());
+    fn test_max_red_wait_tracks_red_duration() {
+        let mut controller = TrafficLightController::new();
+        controller.set_red_duration(7000).unwrap();
+        assert_eq!(controller.max_red_wait(), 7000);
     }
 
+    // ==================== from_preset Tests ====================
+
     #[test]
-    fn test_controller_copy() {
-        let ctrl1 = TrafficLightController::new();
-        let ctrl2 = ctrl1;
-        assert_eq!(ctrl1, ctrl2);
+    fn test_from_preset_applies_plan() {
+        for preset in [
+            TimingPreset::Residential,
+            TimingPreset::Arterial,
+            TimingPreset::Highway,
+        ] {
+            let controller = TrafficLightController::from_preset(preset);
+            assert_eq!(controller.timing_plan(), preset.plan());
+            assert_eq!(controller.current_state(), TrafficLightState::Red);
+            assert!(controller.is_valid());
+        }
     }
 
     #[test]
-    fn test_controller_partial_eq() {
-        let ctrl1 = TrafficLightController::new();
-        let ctrl2 = TrafficLightController::new();
-        assert_eq!(ctrl1, ctrl2);
+    fn test_from_preset_distinct_cycles() {
+        let residential = TrafficLightController::from_preset(TimingPreset::Residential);
+        let arterial = TrafficLightController::from_preset(TimingPreset::Arterial);
+        let highway = TrafficLightController::from_preset(TimingPreset::Highway);
+        assert!(residential.total_cycle_duration() < arterial.total_cycle_duration());
+        assert!(arterial.total_cycle_duration() < highway.total_cycle_duration());
     }
 
+    // ==================== Vehicle Decision Tests ====================
+
     #[test]
-    fn test_controller_debug() {
-        let ctrl = TrafficLightController::new();
-        let debug_str = format!("{:?}", ctrl);
-        assert!(debug_str.contains("TrafficLightController"));
+    fn test_vehicle_decisions_red() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::Red);
+        assert!(!controller.is_safe_to_proceed());
+        assert!(!controller.should_prepare_to_stop());
     }
 
     #[test]
-    fn test_controller_debug_contains_state() {
-        let ctrl = TrafficLightController::new();
-        let debug_str = format!("{:?}", ctrl);
-        assert!(debug_str.contains("Red"));
+    fn test_vehicle_decisions_yellow() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::Yellow);
+        assert!(!controller.is_safe_to_proceed());
+        assert!(controller.should_prepare_to_stop());
     }
 
-    // ==================== State Transition Tests ====================
-
     #[test]
-    fn test_sequence_cycle_red_to_green() {
-        let mut ctrl = TrafficLightController::new();
-        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
-        ctrl.advance();
-        assert_eq!(ctrl.current_state(), TrafficLightState::Green);
+    fn test_vehicle_decisions_green() {
+        let controller = TrafficLightController::starting_at(TrafficLightState::Green);
+        assert!(controller.is_safe_to_proceed());
+        assert!(!controller.should_prepare_to_stop());
     }
 
     #[test]
-    fn test_sequence_cycle_green_to_yellow() {
-        let mut ctrl = TrafficLightController::new();
-        ctrl.advance();
-        ctrl.advance();
-        assert_eq!(ctrl.current_state(), TrafficLightState::Yellow);
+    fn test_vehicle_decisions_optional_phases() {
+        for state in [TrafficLightState::AllRed, TrafficLightState::RedYellow] {
+            let controller = TrafficLightController::starting_at(state);
+            assert!(!controller.is_safe_to_proceed());
+            assert!(!controller.should_prepare_to_stop());
+        }
     }
 
-    #[test]
-    fn test_sequence_cycle_yellow_to_red() {
-        let mut ctrl = TrafficLightController::new();
-        ctrl.advance();
-        ctrl.advance();
-        ctrl.advance();
-        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+    // ==================== tick() Large Step Tests ====================
+
+    /// Ticks a controller in small steps for comparison with one large tick.
+    fn tick_in_steps(controller: &mut TrafficLightController, total: u64, step: u64) {
+        let mut left = total;
+        while left > 0 {
+            let dt = left.min(step);
+            controller.tick(dt);
+            left -= dt;
+        }
     }
 
     #[test]
-    fn test_independent_controllers() {
-        let mut ctrl1 = TrafficLightController::new();
-        let ctrl2 = TrafficLightController::new();
-        ctrl1.advance();
-        assert_eq!(ctrl2.current_state(), TrafficLightState::Red);
-        assert_eq!(ctrl1.current_state(), TrafficLightState::Green);
+    fn test_tick_u64_max_returns() {
+        let mut controller = TrafficLightController::new();
+        let period = controller.total_cycle_duration();
+        controller.tick(u64::MAX);
+        let mut reference = TrafficLightController::new();
+        reference.tick(u64::MAX % period);
+        assert_eq!(controller.current_state(), reference.current_state());
+        assert_eq!(controller.elapsed_in_phase(), reference.elapsed_in_phase());
+        assert_eq!(controller.cycle_count(), u32::MAX);
     }
 
-    // ==================== Edge Case Tests ====================
-
     #[test]
-    fn test_many_controllers() {
-        let controllers: Vec<TrafficLightController> =
-            (0..100).map(|_| TrafficLightController::new()).collect();
-        for ctrl in controllers {
-            assert_eq!(ctrl.red_duration(), RED_DURATION_MS);
-        }
+    fn test_tick_u64_max_preempted_cycle_position() {
+        let mut controller = TrafficLightController::new();
+        controller.preempt_green();
+        controller.tick(u64::MAX);
+        assert!(controller.ms_since_cycle_start() >= RED_DURATION_MS);
+        let other = TrafficLightController::new();
+        assert!(phase_difference_ms(&controller, &other) < controller.total_cycle_duration());
+        assert!(phase_difference_ms(&other, &controller) < controller.total_cycle_duration());
     }
 
     #[test]
-    fn test_controller_in_option() {
-        let maybe_ctrl: Option<TrafficLightController> = Some(TrafficLightController::new());
-        assert!(maybe_ctrl.is_some());
-        assert_eq!(maybe_ctrl.unwrap().red_duration(), RED_DURATION_MS);
+    fn test_tick_whole_cycles_match_stepping() {
+        let mut big = TrafficLightController::new().with_all_red(true);
+        big.tick(700);
+        let mut small = big;
+        let total = 25 * big.total_cycle_duration() + 2345;
+        big.tick(total);
+        tick_in_steps(&mut small, total, 100);
+        assert_eq!(big, small);
     }
 
     #[test]
-    fn test_controller_in_result() {
-        let result: Result<TrafficLightController, ()> = Ok(TrafficLightController::new());
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap().red_duration(), RED_DURATION_MS);
+    fn test_tick_whole_cycles_match_stepping_red_yellow() {
+        let mut big = TrafficLightControllerBuilder::new()
+            .red_yellow(true)
+            .build()
+            .unwrap();
+        let mut small = big;
+        let total = 13 * big.total_cycle_duration() + 999;
+        big.tick(total);
+        tick_in_steps(&mut small, total, 250);
+        assert_eq!(big, small);
     }
 
     #[test]
-    fn test_advance_100_times() {
-        let mut ctrl = TrafficLightController::new();
-        for _ in 0..99 {
-            ctrl.advance();
-        }
-        assert_eq!(ctrl.current_state(), TrafficLightState::Red);
+    fn test_tick_huge_while_flashing() {
+        let mut controller = TrafficLightController::new();
+        controller.enter_fault_mode();
+        controller.tick(u64::MAX);
+        assert!(controller.is_fault());
+        assert_eq!(controller.current_state(), TrafficLightState::Red);
     }
 
     #[test]
-    fn test_controller_size() {
-        assert!(core::mem::size_of::<TrafficLightController>() <= 32);
+    fn test_tick_flashing_matches_stepping() {
+        let mut big = TrafficLightController::new();
+        big.set_night_mode(true);
+        let mut small = big;
+        big.tick(40 * NIGHT_FLASH_MS + 123);
+        tick_in_steps(&mut small, 40 * NIGHT_FLASH_MS + 123, 50);
+        assert_eq!(big, small);
     }
 
     #[test]
-    fn test_controller_alignment() {
-        assert!(core::mem::align_of::<TrafficLightController>() <= 8);
+    fn test_tick_flash_red_test_ends_then_skips() {
+        let mut big = TrafficLightController::new();
+        big.flash_red(7);
+        let mut small = big;
+        let total = 7 * FAULT_FLASH_MS + 10 * big.total_cycle_duration() + 50;
+        big.tick(total);
+        tick_in_steps(&mut small, total, 50);
+        assert_eq!(big, small);
+        assert_eq!(big.flashes_remaining(), 0);
+        assert_eq!(big.mode(), ControllerMode::Normal);
     }
 }